@@ -0,0 +1,288 @@
+use crate::commands::init::{InitOptions, plan_project};
+use crate::config::Config;
+use crate::templates::names::ProjectName;
+use anyhow::{Context, Result};
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+/// A plugin crate that is already a member of the workspace.
+struct Member {
+    dir: String,
+    package_name: Option<String>,
+    /// The plugin's `NAME`, which is also its name in `bundler.toml`.
+    display_name: Option<String>,
+    vst3_class_id: Option<String>,
+    clap_id: Option<String>,
+}
+
+/// Adds a plugin crate to the workspace containing `options.path`. Only the files inside the new
+/// crate's directory are taken from the templates, the workspace manifest and `bundler.toml` are
+/// edited in place.
+pub fn plugin(options: &InitOptions) -> Result<()> {
+    let names = ProjectName::parse(&options.name).map_err(anyhow::Error::msg)?;
+    let name = &names.crate_name;
+
+    let root = find_workspace_root(Path::new(&options.path))?;
+    let manifest_path = root.join("Cargo.toml");
+    let mut manifest: DocumentMut = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?
+        .parse()
+        .with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+
+    let members = read_members(&root, &manifest);
+    if let Some(member) = members.iter().find(|member| {
+        member.dir == names.module_name || member.package_name.as_deref() == Some(name.as_str())
+    }) {
+        anyhow::bail!(
+            "The workspace already has a member named '{}' in '{}'.",
+            name,
+            member.dir
+        );
+    }
+    if root.join(&names.module_name).exists() {
+        anyhow::bail!(
+            "'{}' already exists in the workspace.",
+            root.join(&names.module_name).display()
+        );
+    }
+
+    let mut config = Config::load()?;
+    config.apply(&options.overrides);
+
+    let mut plan = plan_project(options, &names, &config)?;
+    let bundler_template = plan.get(Path::new("bundler.toml")).cloned();
+    plan.files
+        .retain(|file| file.path.starts_with(&names.module_name));
+
+    let lib_path = Path::new(&names.module_name).join("src").join("lib.rs");
+    if let Some(lib) = plan.get(&lib_path) {
+        check_ids(&members, &lib.content)?;
+    }
+
+    add_member(&mut manifest, &names.module_name)?;
+    plan.add(PathBuf::from("Cargo.toml"), manifest.to_string());
+
+    let bundler_path = root.join("bundler.toml");
+    let bundler = if bundler_path.exists() {
+        let mut bundler: DocumentMut = fs::read_to_string(&bundler_path)
+            .with_context(|| format!("Failed to read {}", bundler_path.display()))?
+            .parse()
+            .with_context(|| format!("Failed to parse {}", bundler_path.display()))?;
+        if bundler.contains_key(name) {
+            anyhow::bail!("bundler.toml already has an entry for '{}'.", name);
+        }
+
+        let mut table = Table::new();
+        table["name"] = toml_edit::value(options.display_name(&names));
+        bundler.insert(name, Item::Table(table));
+        Some(bundler.to_string())
+    } else {
+        bundler_template
+            .map(|file| bundler_for_members(&file.content, &members, name))
+            .transpose()?
+    };
+    if let Some(bundler) = bundler {
+        plan.add(PathBuf::from("bundler.toml"), bundler);
+    }
+
+    plan.write_into(&root)?;
+
+    println!(
+        "{} Added plugin {} to the workspace at '{}'.",
+        style("===>").green(),
+        name,
+        root.display()
+    );
+    println!("\n{} To build the new plugin:", style("===>").green());
+    println!("  cargo xtask bundle {} --release", name);
+
+    Ok(())
+}
+
+/// The closest directory at or above `path` with a `Cargo.toml` that has a `[workspace]` table.
+fn find_workspace_root(path: &Path) -> Result<PathBuf> {
+    let start = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read directory: {}", path.display()))?;
+
+    for dir in start.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        let Ok(content) = fs::read_to_string(&manifest) else {
+            continue;
+        };
+
+        let document: DocumentMut = content
+            .parse()
+            .with_context(|| format!("Failed to parse {}", manifest.display()))?;
+        if document.contains_key("workspace") {
+            return Ok(dir.to_path_buf());
+        }
+    }
+
+    anyhow::bail!(
+        "No Cargo workspace found in '{}' or its parents. Run this inside a project created with 'nih-bootstrap init'.",
+        start.display()
+    )
+}
+
+fn read_members(root: &Path, manifest: &DocumentMut) -> Vec<Member> {
+    let Some(members) = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(Item::as_array)
+    else {
+        return Vec::new();
+    };
+
+    members
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|dir| {
+            let package_name = fs::read_to_string(root.join(dir).join("Cargo.toml"))
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .and_then(|manifest| {
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(str::to_string)
+                });
+            let lib =
+                fs::read_to_string(root.join(dir).join("src").join("lib.rs")).unwrap_or_default();
+
+            Member {
+                dir: dir.to_string(),
+                package_name,
+                display_name: str_const(&lib, "NAME"),
+                vst3_class_id: const_value(&lib, "VST3_CLASS_ID"),
+                clap_id: const_value(&lib, "CLAP_ID"),
+            }
+        })
+        .collect()
+}
+
+/// Refuses plugin IDs that an existing member already uses, hosts would treat both plugins as
+/// the same one.
+fn check_ids(members: &[Member], lib: &str) -> Result<()> {
+    let vst3_class_id = const_value(lib, "VST3_CLASS_ID");
+    let clap_id = const_value(lib, "CLAP_ID");
+
+    for member in members {
+        if vst3_class_id.is_some() && member.vst3_class_id == vst3_class_id {
+            anyhow::bail!(
+                "The VST3 class ID is already used by '{}'. Pass --random-id to generate a new one.",
+                member.dir
+            );
+        }
+        if clap_id.is_some() && member.clap_id == clap_id {
+            anyhow::bail!(
+                "The CLAP ID is already used by '{}'. Pass a different name or change the vendor URL.",
+                member.dir
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The value of `const <name>: ... = <value>;` in Rust source, with whitespace removed.
+fn const_value(source: &str, name: &str) -> Option<String> {
+    let start = source.find(&format!("const {}:", name))?;
+    let rest = &source[start..];
+    let rest = &rest[rest.find('=')? + 1..];
+    let value = &rest[..rest.find(';')?];
+
+    Some(value.chars().filter(|c| !c.is_whitespace()).collect())
+}
+
+/// A new `bundler.toml` for a workspace that has none, from the one rendered for the new plugin.
+/// The existing plugins get an entry too, otherwise their bundles would lose their names.
+fn bundler_for_members(rendered: &str, members: &[Member], name: &str) -> Result<String> {
+    let mut bundler: DocumentMut = rendered
+        .parse()
+        .context("Failed to parse the rendered bundler.toml")?;
+    let Some(Item::Table(new_plugin)) = bundler.remove(name) else {
+        return Ok(rendered.to_string());
+    };
+
+    let mut tables: Vec<_> = members
+        .iter()
+        .filter_map(|member| {
+            let mut table = Table::new();
+            table["name"] = toml_edit::value(member.display_name.as_deref()?);
+            Some((member.package_name.clone()?, table))
+        })
+        .collect();
+    // the comment at the top of the file stays in front of the first table
+    let header = new_plugin.decor().prefix().cloned();
+    tables.push((name.to_string(), new_plugin));
+
+    for (index, (key, mut table)) in tables.into_iter().enumerate() {
+        match (&header, index) {
+            (Some(header), 0) => table.decor_mut().set_prefix(header.clone()),
+            _ => table.decor_mut().set_prefix("\n"),
+        }
+        bundler.insert(&key, Item::Table(table));
+    }
+
+    Ok(bundler.to_string())
+}
+
+/// The value of a `const <name>: &str = "...";` string literal in Rust source, unescaped.
+fn str_const(source: &str, name: &str) -> Option<String> {
+    let start = source.find(&format!("const {}:", name))?;
+    let rest = &source[start..];
+    let rest = rest[rest.find('=')? + 1..].trim_start().strip_prefix('"')?;
+
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                '0' => value.push('\0'),
+                'u' => {
+                    let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                    value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+/// Adds `dir` to `[workspace] members`, before `xtask` if it is listed, formatted like the
+/// neighbouring entry.
+fn add_member(manifest: &mut DocumentMut, dir: &str) -> Result<()> {
+    let workspace = manifest
+        .entry("workspace")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .context("[workspace] in Cargo.toml is not a table.")?;
+    let members = workspace
+        .entry("members")
+        .or_insert(toml_edit::value(Array::new()))
+        .as_array_mut()
+        .context("workspace.members in Cargo.toml is not an array.")?;
+
+    let index = members
+        .iter()
+        .position(|member| member.as_str() == Some("xtask"))
+        .unwrap_or(members.len());
+    let neighbour = members.get(index).or_else(|| members.iter().last());
+
+    let mut value = Value::from(dir);
+    if let Some(neighbour) = neighbour {
+        *value.decor_mut() = neighbour.decor().clone();
+    }
+    members.insert_formatted(index, value);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use crate::config::{self, Config, PartialConfig};
+use anyhow::{Context, Result};
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub fn get(key: &str) -> Result<()> {
+    let config = Config::load()?;
+
+    match config.get(key) {
+        Some(value) => println!("{}", value),
+        None => anyhow::bail!(
+            "Unknown config key '{}'. Valid keys are: {}.",
+            key,
+            config::KEYS.join(", ")
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn set(key: &str, value: &str, local: bool) -> Result<()> {
+    config::validate(key, value).map_err(anyhow::Error::msg)?;
+
+    let path = config_path(local)?;
+    let content = if path.exists() {
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let mut document: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    document[key] = toml_edit::value(value);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(&path, document.to_string())
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+
+    println!(
+        "{} Set {} = \"{}\" in {}.",
+        style("===>").green(),
+        key,
+        value,
+        path.display()
+    );
+
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let (config, sources) = Config::load_with_sources()?;
+
+    for (key, source) in sources {
+        println!(
+            "{} = \"{}\" {}",
+            key,
+            config.get(key).unwrap_or_default(),
+            style(format!("({})", source)).dim()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn path(local: bool) -> Result<()> {
+    println!("{}", config_path(local)?.display());
+
+    Ok(())
+}
+
+pub fn edit(local: bool) -> Result<()> {
+    let path = config_path(local)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let content = if path.exists() {
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?
+    } else {
+        commented_defaults()?
+    };
+
+    // the editor works on a copy, so the config is only replaced once the edits are valid
+    let copy = tempfile::Builder::new()
+        .prefix("config-")
+        .suffix(".toml")
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+    fs::write(copy.path(), content)
+        .with_context(|| format!("Failed to write {}", copy.path().display()))?;
+
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| default_editor().to_string());
+
+    // editors are often configured with flags, e.g. `code --wait`
+    let words = shell_words::split(&editor)
+        .with_context(|| format!("Failed to parse editor command '{}'.", editor))?;
+    let Some((program, args)) = words.split_first() else {
+        anyhow::bail!("The editor command is empty. Set VISUAL or EDITOR to an editor.");
+    };
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(copy.path())
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'.", editor))?;
+
+    if !status.success() {
+        anyhow::bail!(
+            "Editor '{}' exited with an error, {} was not changed.",
+            editor,
+            path.display()
+        );
+    }
+
+    if let Err(e) = validate_file(copy.path()) {
+        // keep the edits around instead of throwing them away
+        let (_, edited) = copy
+            .keep()
+            .context("Failed to keep the edited config file.")?;
+
+        return Err(e.context(format!(
+            "{} was not changed, your edits are in {}.",
+            path.display(),
+            edited.display()
+        )));
+    }
+
+    let edited = fs::read_to_string(copy.path())
+        .with_context(|| format!("Failed to read {}", copy.path().display()))?;
+    fs::write(&path, edited)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+
+    println!("{} Saved {}.", style("===>").green(), path.display());
+
+    Ok(())
+}
+
+/// Loads a config file and checks every value in it.
+fn validate_file(path: &Path) -> Result<()> {
+    let layer = PartialConfig::load(path)?;
+    let mut invalid = Vec::new();
+    for key in config::KEYS {
+        if let Some(value) = layer.get(key)
+            && let Err(e) = config::validate(key, value)
+        {
+            invalid.push(format!("  - {}: {}", key, e));
+        }
+    }
+
+    if !invalid.is_empty() {
+        anyhow::bail!(
+            "{} contains invalid values:\n{}",
+            path.display(),
+            invalid.join("\n")
+        );
+    }
+
+    Ok(())
+}
+
+fn config_path(local: bool) -> Result<PathBuf> {
+    if local {
+        return Ok(config::local_config_path());
+    }
+
+    config::user_config_path().context(
+        "Could not determine the user config directory. Set XDG_CONFIG_HOME or HOME, or use --local.",
+    )
+}
+
+/// A starting point for a new config file, with every key present but commented out.
+fn commented_defaults() -> Result<String> {
+    let config = Config::load()?;
+    let mut content = String::from("# Uncomment a value to override it.\n");
+
+    for key in config::KEYS {
+        content.push_str(&format!(
+            "# {} = {}\n",
+            key,
+            toml_edit::value(config.get(key).unwrap_or_default())
+        ));
+    }
+
+    Ok(content)
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) { "notepad" } else { "vi" }
+}
@@ -1,15 +1,35 @@
 use crate::platform;
 use crate::platform::Platform;
+use crate::platform::toolchain::Toolchain;
 use anyhow::Result;
 use console::style;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Dependency {
     pub name: &'static str,
     pub info: &'static str,
 }
 
+/// An installed dependency and the version that was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installed {
+    pub name: String,
+    pub version: String,
+}
+
+/// The outcome of checking a platform's dependencies.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Installed dependencies, for platforms that can tell their versions.
+    pub installed: Vec<Installed>,
+    pub missing: Vec<Dependency>,
+    /// Dependencies that could not be checked because a missing one is needed to check them.
+    pub skipped: Vec<Dependency>,
+}
+
 pub async fn check_and_install(install: bool) -> Result<()> {
     let platform = platform::current();
+    let toolchain = Toolchain::new(&platform);
 
     println!(
         "{} Checking dependencies for {}.",
@@ -17,9 +37,33 @@ pub async fn check_and_install(install: bool) -> Result<()> {
         platform.name()
     );
 
-    let missing = platform.check_dependencies().await?;
+    let system = platform.check_dependencies().await?;
+    let rust = toolchain.check();
+
+    let installed: Vec<_> = system.installed.iter().chain(&rust.installed).collect();
+    if !installed.is_empty() {
+        println!("\n{} Found:", style("===>").green());
+        for dep in installed {
+            if dep.version.is_empty() {
+                println!("  - {}", dep.name);
+            } else {
+                println!("  - {} {}", dep.name, dep.version);
+            }
+        }
+    }
+
+    let skipped: Vec<_> = system.skipped.iter().chain(&rust.skipped).collect();
+    if !skipped.is_empty() {
+        println!(
+            "\n{} Not checked until the missing tools are installed:",
+            style("===>").yellow()
+        );
+        for dep in skipped {
+            println!("  - {} - {}", dep.name, dep.info);
+        }
+    }
 
-    if missing.is_empty() {
+    if system.missing.is_empty() && rust.missing.is_empty() {
         println!(
             "{} All required dependencies are installed.",
             style("===>").green()
@@ -28,7 +72,7 @@ pub async fn check_and_install(install: bool) -> Result<()> {
     }
 
     println!("\n{} Missing dependencies:", style("===>").yellow());
-    for dep in &missing {
+    for dep in system.missing.iter().chain(&rust.missing) {
         println!("  - {} - {}", dep.name, dep.info);
     }
 
@@ -37,14 +81,24 @@ pub async fn check_and_install(install: bool) -> Result<()> {
             "\n{} Installing missing dependencies...",
             style("===>").green()
         );
-        platform.install_dependencies(&missing).await?;
+        if !system.missing.is_empty() {
+            platform.install_dependencies(&system.missing).await?;
+        }
+        if !rust.missing.is_empty() {
+            toolchain.install(&rust.missing)?;
+        }
         println!("{} Installation complete.", style("===>").green());
     } else {
         println!(
             "\n{} To install missing dependencies:",
             style("===>").yellow()
         );
-        println!("{}", platform.install_instructions(&missing));
+        if !system.missing.is_empty() {
+            println!("{}", platform.install_instructions(&system.missing));
+        }
+        if !rust.missing.is_empty() {
+            println!("{}", toolchain.install_instructions(&rust.missing));
+        }
         println!("\nOr run: nih-bootstrap deps --install to install them automatically.");
     }
 
@@ -1,10 +1,36 @@
+use crate::templates::engine;
 use anyhow::{Context, Result};
 use console::style;
+use minijinja::context;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
-pub async fn init_repo(project_path: &Path, init_ci: bool, project_name: &str) -> Result<()> {
+/// System packages the CI runners need to build any plugin.
+const CI_AUDIO_PACKAGES: &[&str] = &["libasound2-dev", "libjack-jackd2-dev"];
+
+/// System packages the CI runners only need to build a plugin GUI.
+const CI_GUI_PACKAGES: &[&str] = &[
+    "libx11-dev",
+    "libx11-xcb-dev",
+    "libxcb1-dev",
+    "libxcb-util-dev",
+    "libxcb-render0-dev",
+    "libxcb-shape0-dev",
+    "libxcb-xfixes0-dev",
+    "libxcb-icccm4-dev",
+    "libxcb-dri2-0-dev",
+    "mesa-common-dev",
+    "libgl1-mesa-dev",
+    "libxcursor-dev",
+];
+
+/// Where the CI/CD workflow is written, relative to the project directory.
+pub const CI_WORKFLOW_PATH: &str = ".github/workflows/general.yaml";
+
+/// Initializes a repository in `project_path` and commits the project. The CI/CD workflow is part
+/// of the generated files, see [`ci_workflow`].
+pub async fn init_repo(project_path: &Path, project_name: &str) -> Result<()> {
     println!("{} Setting up Git repository...", style("===>").green());
 
     // git repository initialization
@@ -21,14 +47,13 @@ pub async fn init_repo(project_path: &Path, init_ci: bool, project_name: &str) -
         );
     }
 
-    // .gitignore file
-    let gitignore_content = include_str!("../..//templates/git/gitignore.txt");
-
-    fs::write(project_path.join(".gitignore"), gitignore_content)
-        .context("Failed to create .gitignore file.")?;
+    // .gitignore file, keeping the one of an existing repository
+    let gitignore_path = project_path.join(".gitignore");
+    if !gitignore_path.exists() {
+        let gitignore_content = engine::render_builtin("git/gitignore.txt", ())?;
 
-    if init_ci {
-        setup_ci(project_path, project_name)?;
+        fs::write(gitignore_path, gitignore_content)
+            .context("Failed to create .gitignore file.")?;
     }
 
     // stage files
@@ -98,13 +123,13 @@ pub async fn setup(init_ci: bool) -> Result<()> {
     // ensure .gitignore exists
     let gitignore_path = Path::new(".gitignore");
     if !gitignore_path.exists() {
-        let gitignore_content = include_str!("../../templates/git/gitignore.txt");
+        let gitignore_content = engine::render_builtin("git/gitignore.txt", ())?;
         fs::write(gitignore_path, gitignore_content).context("Failed to create .gitignore")?;
         println!("{} Created .gitignore file.", style("===>").green());
     }
 
     if init_ci {
-        setup_ci(&current_dir, project_name)?;
+        setup_ci(&current_dir, project_name, project_has_gui(&current_dir))?;
     }
 
     println!("{} Git setup complete.", style("===>").green());
@@ -117,14 +142,38 @@ pub async fn setup(init_ci: bool) -> Result<()> {
     Ok(())
 }
 
-fn setup_ci(project_path: &Path, project_name: &str) -> Result<()> {
+/// Renders the CI/CD workflow. GUI projects need the X11 and OpenGL development packages too.
+pub fn ci_workflow(has_gui: bool) -> Result<String> {
+    let mut packages = CI_AUDIO_PACKAGES.to_vec();
+    if has_gui {
+        packages.extend(CI_GUI_PACKAGES);
+    }
+    packages.push("pkg-config");
+
+    engine::render_builtin(
+        "git/ci_cd_general.yaml",
+        context! { system_packages => packages },
+    )
+}
+
+fn setup_ci(project_path: &Path, project_name: &str, has_gui: bool) -> Result<()> {
     println!("{} Setting up CI/CD workflows...", style("===>").green());
 
-    let workflows_dir = project_path.join(".github").join("workflows");
-    fs::create_dir_all(&workflows_dir).context("Failed to create .github/workflows directory.")?;
+    let workflow_path = project_path.join(CI_WORKFLOW_PATH);
+    if workflow_path.exists() {
+        println!(
+            "{} {} already exists, leaving it unchanged.",
+            style("===>").yellow(),
+            CI_WORKFLOW_PATH
+        );
+        return Ok(());
+    }
 
-    let ci_content = include_str!("../../templates/git/ci_cd_general.yaml");
-    fs::write(workflows_dir.join("general.yaml"), ci_content)
+    if let Some(workflows_dir) = workflow_path.parent() {
+        fs::create_dir_all(workflows_dir)
+            .context("Failed to create .github/workflows directory.")?;
+    }
+    fs::write(&workflow_path, ci_workflow(has_gui)?)
         .context("Failed to create CI/CD workflow file.")?;
 
     println!(
@@ -136,6 +185,27 @@ fn setup_ci(project_path: &Path, project_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Whether any crate in the workspace at `project_path` depends on a nih-plug GUI crate. Assumes
+/// a GUI when no manifest can be read, so CI installs too much rather than too little.
+fn project_has_gui(project_path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(project_path) else {
+        return true;
+    };
+
+    let manifests: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("Cargo.toml"))
+        .filter_map(|manifest| fs::read_to_string(manifest).ok())
+        .collect();
+
+    manifests.is_empty()
+        || manifests.iter().any(|manifest| {
+            ["nih_plug_iced", "nih_plug_egui", "nih_plug_vizia"]
+                .iter()
+                .any(|gui_crate| manifest.contains(gui_crate))
+        })
+}
+
 pub fn is_git_installed() -> bool {
     Command::new("git")
         .arg("--version")
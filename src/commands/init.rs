@@ -1,45 +1,126 @@
-use crate::config::Config;
-use crate::templates::{TemplateContext, TemplateRenderer};
+use crate::commands::git;
+use crate::config::{Config, PartialConfig};
+use crate::templates::{
+    FilePlan, GuiFramework, PluginFormat, PluginKind, TemplateContext, TemplatePack,
+    TemplateRenderer, ids, names::ProjectName,
+};
 use anyhow::Result;
-use console::style;
+use console::{Term, style};
+use dialoguer::Select;
+use dialoguer::theme::ColorfulTheme;
+use similar::TextDiff;
 use std::fs;
-use std::path::Path;
-
-pub async fn run(
-    name: &str,
-    path: &str,
-    description: &str,
-    init_git: bool,
-    init_ci: bool,
-    gui: &str,
-) -> Result<()> {
-    println!("{} Creating new plugin: {}.", style("===>").green(), name);
+use std::path::{Path, PathBuf};
+
+/// Everything needed to create a project, whether it came from CLI flags or the wizard.
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    pub name: String,
+    /// The name hosts show. Defaults to the title-cased project name.
+    pub display_name: Option<String>,
+    pub path: String,
+    pub description: String,
+    pub init_git: bool,
+    pub init_ci: bool,
+    pub gui: GuiFramework,
+    pub kind: PluginKind,
+    pub formats: Vec<PluginFormat>,
+    pub random_id: bool,
+    /// A template pack directory, or the name of a pack in the templates directory.
+    pub template: Option<String>,
+    /// Only print the files that would be created.
+    pub dry_run: bool,
+    /// Files to print the rendered content of instead of creating the project.
+    pub show: Vec<String>,
+    /// Overwrite files that already exist in the target directory without asking.
+    pub force: bool,
+    pub overrides: PartialConfig,
+}
+
+impl InitOptions {
+    /// The display name, falling back to the title-cased project name.
+    pub fn display_name<'a>(&'a self, names: &'a ProjectName) -> &'a str {
+        self.display_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|display_name| !display_name.is_empty())
+            .unwrap_or(&names.display_name)
+    }
+}
+
+pub async fn run(options: &InitOptions) -> Result<()> {
+    let InitOptions {
+        name,
+        path,
+        init_git,
+        init_ci,
+        gui,
+        formats,
+        dry_run,
+        show,
+        force,
+        overrides,
+        ..
+    } = options;
+    let (init_git, init_ci) = (*init_git, *init_ci);
 
-    let config = Config::load()?;
-    let renderer = TemplateRenderer::new(gui);
+    let names = ProjectName::parse(name).map_err(anyhow::Error::msg)?;
+    let name = &names.crate_name;
 
-    let underscored = name.replace("-", "_");
-    let camelcase = to_camelcase(&underscored);
+    let mut config = Config::load()?;
+    config.apply(overrides);
 
     let target_path = Path::new(path).join(name);
-    if target_path.exists() {
-        anyhow::bail!("Directory '{}' already exists!", target_path.display());
+    if target_path.exists() && !target_path.is_dir() {
+        anyhow::bail!("'{}' exists and is not a directory.", target_path.display());
+    }
+
+    let mut plan = plan_project(options, &names, &config)?;
+    if init_git && init_ci {
+        // goes through the same collision handling as the project files
+        plan.add(
+            PathBuf::from(git::CI_WORKFLOW_PATH),
+            git::ci_workflow(gui.has_editor())?,
+        );
     }
 
-    fs::create_dir_all(&target_path)?;
+    if !show.is_empty() {
+        return show_files(&plan, show);
+    }
+
+    println!("{} Creating new plugin: {}.", style("===>").green(), name);
+
+    if *dry_run {
+        println!(
+            "{} Dry run, nothing was written. Files that would be created:\n",
+            style("===>").green()
+        );
+        print!("{}", plan.tree(&target_path.display().to_string()));
+
+        let collisions = plan.collisions(&target_path);
+        if !collisions.is_empty() {
+            println!("\nThese files already exist and would need --force or a confirmation:");
+            for file in collisions {
+                println!("  {}", file.path.display());
+            }
+        }
+        if init_git {
+            println!(
+                "\nA Git repository{} would be set up as well.",
+                if init_ci { " with CI/CD workflows" } else { "" }
+            );
+        }
+        return Ok(());
+    }
 
     println!("{} Creating project structure...", style("===>").green());
 
-    renderer.render_project(
-        &target_path,
-        &TemplateContext {
-            project_name: name,
-            underscored_name: &underscored,
-            camelcase_name: &camelcase,
-            description,
-            config: &config,
-        },
-    )?;
+    if target_path.exists() {
+        resolve_collisions(&mut plan, &target_path, *force)?;
+        plan.write_into(&target_path)?;
+    } else {
+        plan.write(&target_path)?;
+    }
 
     println!(
         "{} Project files created at '{}'.",
@@ -48,14 +129,14 @@ pub async fn run(
     );
 
     if init_git {
-        if !crate::commands::git::is_git_installed() {
+        if !git::is_git_installed() {
             eprintln!(
                 "{} Warining: Git is not installed. Skipping Git initialization.",
                 style("===>").yellow()
             );
             eprintln!("{} Install Git and run 'git init'.", style("===>").yellow());
         } else {
-            match crate::commands::git::init_repo(&target_path, init_ci, name).await {
+            match git::init_repo(&target_path, name).await {
                 Ok(_) => println!("{} Git repository initialized.", style("===>").green()),
                 Err(e) => {
                     eprintln!(
@@ -75,34 +156,145 @@ pub async fn run(
     show_next_steps(
         &target_path,
         name,
-        init_git && crate::commands::git::is_git_installed(),
+        formats,
+        init_git && git::is_git_installed(),
     );
 
     Ok(())
 }
 
-fn to_camelcase(s: &str) -> String {
-    let mut result = String::new();
-    let mut capitalize_next = true;
+/// Renders every file of the project described by `options` into memory.
+pub fn plan_project(
+    options: &InitOptions,
+    names: &ProjectName,
+    config: &Config,
+) -> Result<FilePlan> {
+    let pack = match &options.template {
+        Some(template) => TemplatePack::resolve(template, config)?,
+        None => TemplatePack::builtin(),
+    };
+    let renderer = TemplateRenderer::new(pack, options.gui, options.kind, &options.formats);
+
+    let class_id = if options.random_id {
+        ids::random_vst3_class_id()?
+    } else {
+        ids::vst3_class_id(&config.vendor, &names.crate_name)
+    };
+    let vst3_class_id = ids::format_class_id(&class_id);
+    let clap_id = ids::clap_id(&config.vendor_url, &config.vendor, &names.crate_name);
+
+    renderer.plan(&TemplateContext {
+        project_name: &names.crate_name,
+        underscored_name: &names.module_name,
+        camelcase_name: &names.type_name,
+        display_name: options.display_name(names),
+        description: &options.description,
+        vst3_class_id: &vst3_class_id,
+        clap_id: &clap_id,
+        config,
+    })
+}
+
+/// Decides what happens to planned files that already exist in `target_path`: all are
+/// overwritten with `force`, otherwise the user is asked per file. Skipped files are removed from
+/// the plan.
+fn resolve_collisions(plan: &mut FilePlan, target_path: &Path, force: bool) -> Result<()> {
+    let collisions: Vec<_> = plan
+        .collisions(target_path)
+        .into_iter()
+        .map(|file| file.path.clone())
+        .collect();
+
+    if collisions.is_empty() || force {
+        return Ok(());
+    }
+
+    if !Term::stdout().is_term() {
+        let files: Vec<_> = collisions
+            .iter()
+            .map(|path| format!("  {}", path.display()))
+            .collect();
+        anyhow::bail!(
+            "These files already exist in '{}':\n{}\nPass --force to overwrite them.",
+            target_path.display(),
+            files.join("\n")
+        );
+    }
+
+    let theme = ColorfulTheme::default();
+    for path in collisions {
+        let existing = fs::read_to_string(target_path.join(&path)).unwrap_or_default();
+
+        loop {
+            let choice = Select::with_theme(&theme)
+                .with_prompt(format!("{} already exists", path.display()))
+                .items(["overwrite", "skip", "diff"])
+                .default(1)
+                .interact()?;
 
-    for c in s.chars() {
-        if c == '_' || c == '-' || c == ' ' {
-            capitalize_next = true;
-            continue;
+            match choice {
+                0 => break,
+                1 => {
+                    plan.remove(&path);
+                    break;
+                }
+                _ => {
+                    if let Some(file) = plan.get(&path) {
+                        print_diff(&path, &existing, &file.content);
+                    }
+                }
+            }
         }
+    }
+
+    Ok(())
+}
 
-        if !capitalize_next {
-            result.push(c);
+fn print_diff(path: &Path, old: &str, new: &str) {
+    let diff = TextDiff::from_lines(old, new);
+    let path = path.display().to_string();
+
+    for line in diff
+        .unified_diff()
+        .header(&format!("{} (existing)", path), &format!("{} (new)", path))
+        .to_string()
+        .lines()
+    {
+        if line.starts_with('+') {
+            println!("{}", style(line).green());
+        } else if line.starts_with('-') {
+            println!("{}", style(line).red());
+        } else if line.starts_with("@@") {
+            println!("{}", style(line).cyan());
         } else {
-            result.push(c.to_ascii_uppercase());
-            capitalize_next = false;
+            println!("{}", line);
         }
     }
+}
+
+/// Prints the rendered content of planned files, given relative to the project directory.
+fn show_files(plan: &FilePlan, paths: &[String]) -> Result<()> {
+    for path in paths {
+        let Some(file) = plan.get(Path::new(path.trim_start_matches("./"))) else {
+            let files: Vec<_> = plan
+                .files
+                .iter()
+                .map(|file| format!("  {}", file.path.display()))
+                .collect();
+            anyhow::bail!(
+                "'{}' is not part of the project. The project contains:\n{}",
+                path,
+                files.join("\n")
+            );
+        };
 
-    result
+        print!("{}", file.content);
+    }
+
+    Ok(())
 }
 
-fn show_next_steps(path: &Path, name: &str, git_initialized: bool) {
+fn show_next_steps(path: &Path, name: &str, formats: &[PluginFormat], git_initialized: bool) {
     println!("\n{} Next steps:", style("===>").green());
     println!("  cd {}", path.display());
 
@@ -112,15 +304,32 @@ fn show_next_steps(path: &Path, name: &str, git_initialized: bool) {
         println!("  git commit -m 'Initial commit for {}'", name);
     }
 
-    println!(
-        "\n{} To build project into a VST3 plugin:",
-        style("===>").green()
-    );
-    println!("  cargo xtask bundle {} --release", name);
+    let plugin_formats: Vec<_> = formats
+        .iter()
+        .filter(|format| **format != PluginFormat::Standalone)
+        .map(|format| format.label())
+        .collect();
+
+    if !plugin_formats.is_empty() {
+        println!(
+            "\n{} To build project into a {} plugin:",
+            style("===>").green(),
+            plugin_formats.join(" and ")
+        );
+        println!("  cargo xtask bundle {} --release", name);
+    }
+
+    if formats.contains(&PluginFormat::Standalone) {
+        println!(
+            "\n{} To run the plugin as a standalone application:",
+            style("===>").green()
+        );
+        println!("  cargo run --package {} --release", name);
+    }
     println!("\n{} To check dependencies: ", style("===>").green());
     println!("  nih-bootstrap deps");
 
-    if crate::commands::git::is_git_installed() && !git_initialized {
+    if git::is_git_installed() && !git_initialized {
         println!("{} To add git and CI/CD later:", style("===>").yellow());
         println!("  nih-bootstrap git --ci");
     }
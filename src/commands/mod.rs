@@ -1,3 +1,7 @@
+pub mod add;
+pub mod config;
 pub mod dependencies;
 pub mod git;
 pub mod init;
+pub mod templates;
+pub mod wizard;
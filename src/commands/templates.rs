@@ -0,0 +1,257 @@
+use crate::config::Config;
+use crate::templates::engine::BUILTIN_TEMPLATES;
+use crate::templates::pack::{BUILTIN_MANIFEST, BuiltinChoice, MANIFEST_FILE};
+use crate::templates::{GuiFramework, PluginKind, TemplatePack, VARIABLES};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn list() -> Result<()> {
+    let config = Config::load()?;
+    let builtin = TemplatePack::builtin();
+
+    println!("{}", style("Built-in:").bold());
+    println!(
+        "  {}  {}",
+        style(&builtin.name).cyan(),
+        builtin.manifest.description
+    );
+
+    let gui_names: Vec<_> = GuiFramework::value_variants()
+        .iter()
+        .filter_map(|gui| gui.to_possible_value())
+        .map(|value| value.get_name().to_string())
+        .collect();
+    println!("    GUI frameworks (--gui): {}", gui_names.join(", "));
+
+    println!("    Plugin kinds (--kind):");
+    for kind in PluginKind::value_variants() {
+        if let Some(value) = kind.to_possible_value() {
+            println!(
+                "      {:<12}{}",
+                value.get_name(),
+                value
+                    .get_help()
+                    .map(|help| help.to_string())
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    let templates_dir = config
+        .templates_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| "no templates directory".to_string());
+    println!(
+        "\n{}",
+        style(format!("User packs ({}):", templates_dir)).bold()
+    );
+
+    let packs = TemplatePack::user_packs(&config);
+    if packs.is_empty() {
+        println!("  none");
+    }
+
+    for pack in packs {
+        match pack {
+            Ok(pack) => println!(
+                "  {}  {}",
+                style(&pack.name).cyan(),
+                pack.manifest.description
+            ),
+            Err(e) => println!("  {} {:#}", style("invalid:").red(), e),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn show(name: &str) -> Result<()> {
+    let config = Config::load()?;
+    let pack = TemplatePack::resolve(name, &config)?;
+
+    println!("{}", style(&pack.name).cyan().bold());
+    if !pack.manifest.description.is_empty() {
+        println!("{}", pack.manifest.description);
+    }
+    match &pack.dir {
+        Some(dir) => println!("Location: {}", dir.display()),
+        None => println!("Location: bundled with nih-bootstrap"),
+    }
+
+    println!("\n{}", style("Files:").bold());
+    for file in pack.files() {
+        print!("  {}  <- {}", file.path, file.template);
+        if let Some(when) = &file.when {
+            print!(" {}", style(format!("(when {})", when)).dim());
+        }
+        println!();
+    }
+
+    println!("\n{}", style("Variables:").bold());
+    for (variable, description) in VARIABLES {
+        println!("  {:<20}{}", variable, description);
+    }
+    for (variable, value) in &pack.manifest.variables {
+        println!("  {:<20}pack variable, = {}", variable, value);
+    }
+
+    if !pack.manifest.requires.is_empty() {
+        println!("\nRequires: {}", pack.manifest.requires.join(", "));
+    }
+    if let Some(gui) = pack.manifest.gui {
+        println!("Only for: --gui {}", BuiltinChoice::Gui(gui).name());
+    }
+    if let Some(kind) = pack.manifest.kind {
+        println!("Only for: --kind {}", BuiltinChoice::Kind(kind).name());
+    }
+
+    Ok(())
+}
+
+/// Writes a pack to `dir` so it can be edited and used with `--template`. The built-in pack, or a
+/// single built-in GUI framework or plugin kind, is exported with `include_builtin = false`.
+pub fn export(name: &str, dir: &Path) -> Result<()> {
+    let config = Config::load()?;
+    let pack = TemplatePack::resolve(name, &config)?;
+
+    if let Some(pack_dir) = &pack.dir {
+        let pack_dir = pack_dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve directory: {}", pack_dir.display()))?;
+
+        // copying would recurse into its own output
+        if canonical_target(dir)?.starts_with(&pack_dir) {
+            anyhow::bail!(
+                "Cannot export template pack '{}' into its own directory: {}",
+                pack.name,
+                dir.display()
+            );
+        }
+    }
+
+    if dir.exists()
+        && fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+            .next()
+            .is_some()
+    {
+        anyhow::bail!("Directory '{}' is not empty.", dir.display());
+    }
+
+    match &pack.dir {
+        Some(pack_dir) => copy_dir(pack_dir, dir)?,
+        None => export_builtin(&pack, dir)?,
+    }
+
+    println!(
+        "{} Exported template pack '{}' to {}.",
+        style("===>").green(),
+        pack.name,
+        dir.display()
+    );
+    println!("  nih-bootstrap init <name> --template {}", dir.display());
+
+    Ok(())
+}
+
+/// Writes the built-in templates with `include_builtin = false`. A pack limited to one GUI
+/// framework or plugin kind only gets that choice's templates.
+fn export_builtin(pack: &TemplatePack, dir: &Path) -> Result<()> {
+    let choice = match (pack.manifest.gui, pack.manifest.kind) {
+        (Some(gui), _) => Some(BuiltinChoice::Gui(gui)),
+        (None, Some(kind)) => Some(BuiltinChoice::Kind(kind)),
+        (None, None) => None,
+    };
+
+    let mut manifest: toml_edit::DocumentMut = BUILTIN_MANIFEST
+        .parse()
+        .context("Failed to parse the built-in template manifest.")?;
+    manifest["include_builtin"] = toml_edit::value(false);
+
+    if let Some(choice) = choice {
+        manifest["description"] = toml_edit::value(&pack.manifest.description);
+        match choice {
+            BuiltinChoice::Gui(_) => manifest["gui"] = toml_edit::value(choice.name()),
+            BuiltinChoice::Kind(_) => manifest["kind"] = toml_edit::value(choice.name()),
+        }
+
+        if let Some(files) = manifest["files"].as_array_of_tables_mut() {
+            files.retain(|file| {
+                file.get("template")
+                    .and_then(|template| template.as_str())
+                    .is_some_and(|template| choice.template(template).is_some())
+            });
+            for file in files.iter_mut() {
+                if let Some(template) = file
+                    .get("template")
+                    .and_then(|template| template.as_str())
+                    .and_then(|template| choice.template(template))
+                {
+                    file["template"] = toml_edit::value(template);
+                }
+            }
+        }
+    }
+
+    write_file(&dir.join(MANIFEST_FILE), &manifest.to_string())?;
+
+    for (template, source) in BUILTIN_TEMPLATES {
+        if choice.is_some_and(|choice| choice.excludes(template)) {
+            continue;
+        }
+
+        write_file(&dir.join(template), source)?;
+    }
+
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)
+        .with_context(|| format!("Failed to create directory: {}", to.display()))?;
+
+    for entry in fs::read_dir(from)
+        .with_context(|| format!("Failed to read directory: {}", from.display()))?
+    {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+
+        if path.is_dir() {
+            copy_dir(&path, &to.join(file_name))?;
+        } else {
+            fs::copy(&path, to.join(file_name))
+                .with_context(|| format!("Failed to copy file: {}", path.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// `path` made absolute with symlinks resolved, for a path that may not exist yet.
+fn canonical_target(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve directory: {}", path.display()))?;
+
+    for ancestor in absolute.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let rest = absolute.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return Ok(canonical.join(rest));
+        }
+    }
+
+    Ok(absolute)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    fs::write(path, content).with_context(|| format!("Failed to write file: {}", path.display()))
+}
@@ -0,0 +1,449 @@
+use crate::commands::init::InitOptions;
+use crate::config::{self, Config, PartialConfig};
+use crate::templates::names::ProjectName;
+use crate::templates::{GuiFramework, PluginFormat, PluginKind};
+use anyhow::Result;
+use clap::ValueEnum;
+use console::{Term, style};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use std::path::Path;
+
+/// Walks the user through every `init` option. Values already given on the command line are
+/// used as the prompt defaults.
+pub fn run(defaults: InitOptions) -> Result<InitOptions> {
+    if !Term::stdout().is_term() {
+        anyhow::bail!(
+            "No project name given and not running in an interactive terminal. Pass a project name."
+        );
+    }
+
+    let theme = ColorfulTheme::default();
+    let config = Config::load()?;
+
+    println!(
+        "{} Answer a few questions to set up your plugin project.\n",
+        style("===>").green()
+    );
+
+    let mut name_prompt = Input::<String>::with_theme(&theme)
+        .with_prompt("Project name (kebab-case, snake_case or CamelCase)")
+        .validate_with(|input: &String| validate_name(input));
+    if !defaults.name.is_empty() {
+        name_prompt = name_prompt.default(defaults.name.clone());
+    }
+    let name = name_prompt.interact_text()?;
+    let crate_name = ProjectName::parse(&name)
+        .map_err(anyhow::Error::msg)?
+        .crate_name;
+
+    let display_name = Input::<String>::with_theme(&theme)
+        .with_prompt("Display name, shown in plugin hosts")
+        .default(default_display_name(
+            defaults.display_name.as_deref(),
+            &name,
+        )?)
+        .interact_text()?;
+
+    let path = Input::<String>::with_theme(&theme)
+        .with_prompt("Directory to create the project in")
+        .default(defaults.path.clone())
+        .validate_with(|input: &String| validate_path(input, &crate_name))
+        .interact_text()?;
+
+    let description = Input::<String>::with_theme(&theme)
+        .with_prompt("Project description")
+        .default(defaults.description.clone())
+        .interact_text()?;
+
+    let gui_frameworks = GuiFramework::value_variants();
+    let gui_labels: Vec<_> = gui_frameworks.iter().map(|gui| gui.label()).collect();
+    let gui_index = Select::with_theme(&theme)
+        .with_prompt("GUI framework")
+        .items(&gui_labels)
+        .default(default_index(gui_frameworks, &defaults.gui))
+        .interact()?;
+    let gui = gui_frameworks[gui_index];
+
+    let kinds = PluginKind::value_variants();
+    let kind_labels: Vec<_> = kinds.iter().map(|kind| kind.label()).collect();
+    let kind_index = Select::with_theme(&theme)
+        .with_prompt("Plugin kind")
+        .items(&kind_labels)
+        .default(default_index(kinds, &defaults.kind))
+        .interact()?;
+    let kind = kinds[kind_index];
+
+    let format_labels: Vec<_> = PluginFormat::value_variants()
+        .iter()
+        .map(|format| format.label())
+        .collect();
+    let formats = loop {
+        let selected = MultiSelect::with_theme(&theme)
+            .with_prompt("Plugin formats (space to toggle, enter to confirm)")
+            .items(&format_labels)
+            .defaults(&format_defaults(&defaults.formats))
+            .interact()?;
+
+        match selected_formats(&selected) {
+            Ok(formats) => break formats,
+            Err(e) => println!("{} {}", style("===>").yellow(), e),
+        }
+    };
+
+    let init_git = Confirm::with_theme(&theme)
+        .with_prompt("Initialize a Git repository?")
+        .default(defaults.init_git)
+        .interact()?;
+
+    let init_ci = init_git
+        && Confirm::with_theme(&theme)
+            .with_prompt("Set up CI/CD workflows?")
+            .default(defaults.init_ci)
+            .interact()?;
+
+    let vendor_defaults = Vendor::defaults(&defaults.overrides, config);
+
+    let vendor = Vendor {
+        vendor: Input::<String>::with_theme(&theme)
+            .with_prompt("Vendor")
+            .default(vendor_defaults.vendor)
+            .allow_empty(true)
+            .interact_text()?,
+        authors: Input::<String>::with_theme(&theme)
+            .with_prompt("Authors")
+            .default(vendor_defaults.authors)
+            .allow_empty(true)
+            .interact_text()?,
+        vendor_url: Input::<String>::with_theme(&theme)
+            .with_prompt("Vendor URL")
+            .default(vendor_defaults.vendor_url)
+            .allow_empty(true)
+            .validate_with(|input: &String| config::validate("vendor_url", input))
+            .interact_text()?,
+        vendor_email: Input::<String>::with_theme(&theme)
+            .with_prompt("Vendor email")
+            .default(vendor_defaults.vendor_email)
+            .allow_empty(true)
+            .validate_with(|input: &String| config::validate("vendor_email", input))
+            .interact_text()?,
+    };
+
+    println!();
+
+    Ok(into_options(
+        defaults,
+        Answers {
+            name,
+            display_name,
+            path,
+            description,
+            gui,
+            kind,
+            formats,
+            init_git,
+            init_ci,
+            vendor,
+        },
+    ))
+}
+
+/// Everything the wizard asks for.
+struct Answers {
+    name: String,
+    display_name: String,
+    path: String,
+    description: String,
+    gui: GuiFramework,
+    kind: PluginKind,
+    formats: Vec<PluginFormat>,
+    init_git: bool,
+    init_ci: bool,
+    vendor: Vendor,
+}
+
+/// The vendor information the wizard asks for, stored as config overrides.
+#[derive(Debug, PartialEq, Eq)]
+struct Vendor {
+    vendor: String,
+    authors: String,
+    vendor_url: String,
+    vendor_email: String,
+}
+
+impl Vendor {
+    /// The values given on the command line, falling back to the configured ones.
+    fn defaults(overrides: &PartialConfig, config: Config) -> Self {
+        Self {
+            vendor: overrides.vendor.clone().unwrap_or(config.vendor),
+            authors: overrides.authors.clone().unwrap_or(config.authors),
+            vendor_url: overrides.vendor_url.clone().unwrap_or(config.vendor_url),
+            vendor_email: overrides
+                .vendor_email
+                .clone()
+                .unwrap_or(config.vendor_email),
+        }
+    }
+}
+
+/// Combines the answers with the options the wizard doesn't ask for.
+fn into_options(defaults: InitOptions, answers: Answers) -> InitOptions {
+    let mut overrides = defaults.overrides;
+    overrides.vendor = Some(answers.vendor.vendor);
+    overrides.authors = Some(answers.vendor.authors);
+    overrides.vendor_url = Some(answers.vendor.vendor_url);
+    overrides.vendor_email = Some(answers.vendor.vendor_email);
+
+    InitOptions {
+        name: answers.name,
+        display_name: Some(answers.display_name),
+        path: answers.path,
+        description: answers.description,
+        init_git: answers.init_git,
+        // CI workflows are only set up in a Git repository
+        init_ci: answers.init_git && answers.init_ci,
+        gui: answers.gui,
+        kind: answers.kind,
+        formats: answers.formats,
+        random_id: defaults.random_id,
+        template: defaults.template,
+        dry_run: defaults.dry_run,
+        show: defaults.show,
+        force: defaults.force,
+        overrides,
+    }
+}
+
+/// The display name given on the command line, or the title-cased project name.
+fn default_display_name(display_name: Option<&str>, name: &str) -> Result<String> {
+    match display_name {
+        Some(display_name) => Ok(display_name.to_string()),
+        None => Ok(ProjectName::parse(name)
+            .map_err(anyhow::Error::msg)?
+            .display_name),
+    }
+}
+
+/// The position of `value` in `variants`, or the first one.
+fn default_index<T: PartialEq>(variants: &[T], value: &T) -> usize {
+    variants
+        .iter()
+        .position(|variant| variant == value)
+        .unwrap_or(0)
+}
+
+/// Which formats start out selected.
+fn format_defaults(formats: &[PluginFormat]) -> Vec<bool> {
+    PluginFormat::value_variants()
+        .iter()
+        .map(|format| formats.contains(format))
+        .collect()
+}
+
+fn selected_formats(selected: &[usize]) -> Result<Vec<PluginFormat>, String> {
+    if selected.is_empty() {
+        return Err("Select at least one format.".to_string());
+    }
+
+    Ok(selected
+        .iter()
+        .map(|i| PluginFormat::value_variants()[*i])
+        .collect())
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    ProjectName::parse(name).map(|_| ())
+}
+
+fn validate_path(path: &str, name: &str) -> Result<(), String> {
+    let base = Path::new(path);
+
+    if base.exists() && !base.is_dir() {
+        return Err(format!("'{}' is not a directory.", base.display()));
+    }
+
+    let target = base.join(name);
+    if target.exists() && !target.is_dir() {
+        return Err(format!(
+            "'{}' exists and is not a directory.",
+            target.display()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> InitOptions {
+        InitOptions {
+            name: String::new(),
+            display_name: None,
+            path: ".".to_string(),
+            description: String::new(),
+            init_git: true,
+            init_ci: true,
+            gui: GuiFramework::Iced,
+            kind: PluginKind::Effect,
+            formats: vec![PluginFormat::Vst3, PluginFormat::Clap],
+            random_id: true,
+            template: Some("team".to_string()),
+            dry_run: true,
+            show: Vec::new(),
+            force: true,
+            overrides: PartialConfig {
+                nih_plug_git: Some("https://example.com/nih-plug.git".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn vendor() -> Vendor {
+        Vendor {
+            vendor: "Vendor".to_string(),
+            authors: "Author".to_string(),
+            vendor_url: "https://example.com".to_string(),
+            vendor_email: "author@example.com".to_string(),
+        }
+    }
+
+    fn answers(init_git: bool, init_ci: bool) -> Answers {
+        Answers {
+            name: "my-plugin".to_string(),
+            display_name: "My Plugin".to_string(),
+            path: "plugins".to_string(),
+            description: "A plugin".to_string(),
+            gui: GuiFramework::None,
+            kind: PluginKind::Midi,
+            formats: vec![PluginFormat::Clap],
+            init_git,
+            init_ci,
+            vendor: vendor(),
+        }
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("my-cool-plugin").is_ok());
+        assert!(validate_name("My Cool Plugin").is_ok());
+        assert!(validate_name("fn").is_err());
+        assert!(validate_name("2band").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn validates_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().to_str().unwrap();
+        std::fs::write(dir.path().join("file"), "").unwrap();
+
+        assert!(validate_path(base, "my_plugin").is_ok());
+        assert!(validate_path(&format!("{}/missing", base), "my_plugin").is_ok());
+        assert!(validate_path(&format!("{}/file", base), "my_plugin").is_err());
+        assert!(validate_path(base, "file").is_err());
+    }
+
+    #[test]
+    fn display_name_defaults_to_the_title_cased_name() {
+        assert_eq!(
+            default_display_name(None, "my_cool_plugin").unwrap(),
+            "My Cool Plugin"
+        );
+        assert_eq!(
+            default_display_name(Some("MCP"), "my_cool_plugin").unwrap(),
+            "MCP"
+        );
+    }
+
+    #[test]
+    fn choices_default_to_the_given_values() {
+        let kinds = PluginKind::value_variants();
+        assert_eq!(
+            kinds[default_index(kinds, &PluginKind::Midi)],
+            PluginKind::Midi
+        );
+        assert_eq!(default_index(&[PluginKind::Effect], &PluginKind::Midi), 0);
+
+        let selected = format_defaults(&[PluginFormat::Clap]);
+        let all = PluginFormat::value_variants();
+        for (format, selected) in all.iter().zip(selected) {
+            assert_eq!(selected, *format == PluginFormat::Clap);
+        }
+    }
+
+    #[test]
+    fn at_least_one_format_is_selected() {
+        assert!(selected_formats(&[]).is_err());
+
+        let all = PluginFormat::value_variants();
+        assert_eq!(selected_formats(&[0, 2]).unwrap(), [all[0], all[2]]);
+    }
+
+    #[test]
+    fn vendor_defaults_prefer_the_command_line() {
+        let overrides = PartialConfig {
+            vendor: Some("Flag Vendor".to_string()),
+            vendor_email: Some(String::new()),
+            ..Default::default()
+        };
+        let config = Config {
+            authors: "Config Author".to_string(),
+            cargo_pkg_version: "0.1.0".to_string(),
+            vendor: "Config Vendor".to_string(),
+            vendor_url: "https://config.example.com".to_string(),
+            vendor_email: "config@example.com".to_string(),
+            nih_plug_git: String::new(),
+            templates_dir: String::new(),
+        };
+
+        assert_eq!(
+            Vendor::defaults(&overrides, config),
+            Vendor {
+                vendor: "Flag Vendor".to_string(),
+                authors: "Config Author".to_string(),
+                vendor_url: "https://config.example.com".to_string(),
+                vendor_email: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn answers_replace_the_prompted_options() {
+        let options = into_options(options(), answers(true, true));
+
+        assert_eq!(options.name, "my-plugin");
+        assert_eq!(options.display_name.as_deref(), Some("My Plugin"));
+        assert_eq!(options.path, "plugins");
+        assert_eq!(options.description, "A plugin");
+        assert_eq!(options.gui, GuiFramework::None);
+        assert_eq!(options.kind, PluginKind::Midi);
+        assert_eq!(options.formats, [PluginFormat::Clap]);
+        assert!(options.init_git && options.init_ci);
+        assert_eq!(options.overrides.vendor.as_deref(), Some("Vendor"));
+        assert_eq!(options.overrides.authors.as_deref(), Some("Author"));
+        assert_eq!(
+            options.overrides.vendor_url.as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            options.overrides.vendor_email.as_deref(),
+            Some("author@example.com")
+        );
+
+        // the options the wizard doesn't ask for are kept
+        assert!(options.random_id && options.dry_run && options.force);
+        assert_eq!(options.template.as_deref(), Some("team"));
+        assert_eq!(
+            options.overrides.nih_plug_git.as_deref(),
+            Some("https://example.com/nih-plug.git")
+        );
+    }
+
+    #[test]
+    fn ci_needs_a_git_repository() {
+        let options = into_options(options(), answers(false, true));
+        assert!(!options.init_git && !options.init_ci);
+    }
+}
@@ -1,7 +1,7 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -11,23 +11,371 @@ pub struct Config {
     pub vendor_url: String,
     pub vendor_email: String,
     pub nih_plug_git: String,
+    pub templates_dir: String,
+}
+
+/// One configuration layer. Only the fields that are set override the layers below it. Unknown
+/// keys are rejected, so a misspelled key isn't silently ignored.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialConfig {
+    pub authors: Option<String>,
+    pub cargo_pkg_version: Option<String>,
+    pub vendor: Option<String>,
+    pub vendor_url: Option<String>,
+    pub vendor_email: Option<String>,
+    pub nih_plug_git: Option<String>,
+    pub templates_dir: Option<String>,
+}
+
+/// The keys that can be read and written with `nih-bootstrap config`.
+pub const KEYS: &[&str] = &[
+    "authors",
+    "cargo_pkg_version",
+    "vendor",
+    "vendor_url",
+    "vendor_email",
+    "nih_plug_git",
+    "templates_dir",
+];
+
+/// The layer a configuration value was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    User,
+    Local,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::User => write!(f, "user"),
+            Source::Local => write!(f, "local"),
+        }
+    }
 }
 
 impl Config {
+    /// Loads the configuration by layering the project-local file over the user file over the
+    /// bundled defaults. CLI flags are applied on top by the caller with [`Config::apply`].
     pub fn load() -> Result<Self> {
-        let local_config = PathBuf::from("./data/general_info.toml");
+        let mut config = Self::default_config()?;
+
+        if let Some(user_config) = user_config_path() {
+            config.apply(&PartialConfig::load(&user_config)?);
+        }
+
+        config.apply(&PartialConfig::load(&local_config_path())?);
+
+        Ok(config)
+    }
+
+    /// Like [`Config::load`], but also reports which layer each key was taken from.
+    pub fn load_with_sources() -> Result<(Self, Vec<(&'static str, Source)>)> {
+        let mut layers = Vec::new();
+        if let Some(user_config) = user_config_path() {
+            layers.push((PartialConfig::load(&user_config)?, Source::User));
+        }
+        layers.push((PartialConfig::load(&local_config_path())?, Source::Local));
+
+        Self::layered(&layers)
+    }
+
+    /// Applies `layers` over the defaults, lowest layer first, and reports which layer each key
+    /// was taken from.
+    fn layered(layers: &[(PartialConfig, Source)]) -> Result<(Self, Vec<(&'static str, Source)>)> {
+        let mut config = Self::default_config()?;
+        let mut sources: Vec<_> = KEYS.iter().map(|key| (*key, Source::Default)).collect();
+
+        for (layer, source) in layers {
+            config.apply(layer);
+
+            for (key, key_source) in &mut sources {
+                if layer.get(key).is_some() {
+                    *key_source = *source;
+                }
+            }
+        }
+
+        Ok((config, sources))
+    }
 
-        if local_config.exists() {
-            let content = fs::read_to_string(&local_config)?;
-            Ok(toml::from_str(&content)?)
-        } else {
-            Self::default_config()
+    pub fn apply(&mut self, layer: &PartialConfig) {
+        for key in KEYS {
+            if let (Some(value), Some(layer_value)) = (self.get_mut(key), layer.get(key)) {
+                *value = layer_value.to_string();
+            }
         }
     }
 
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "authors" => Some(&self.authors),
+            "cargo_pkg_version" => Some(&self.cargo_pkg_version),
+            "vendor" => Some(&self.vendor),
+            "vendor_url" => Some(&self.vendor_url),
+            "vendor_email" => Some(&self.vendor_email),
+            "nih_plug_git" => Some(&self.nih_plug_git),
+            "templates_dir" => Some(&self.templates_dir),
+            _ => None,
+        }
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        match key {
+            "authors" => Some(&mut self.authors),
+            "cargo_pkg_version" => Some(&mut self.cargo_pkg_version),
+            "vendor" => Some(&mut self.vendor),
+            "vendor_url" => Some(&mut self.vendor_url),
+            "vendor_email" => Some(&mut self.vendor_email),
+            "nih_plug_git" => Some(&mut self.nih_plug_git),
+            "templates_dir" => Some(&mut self.templates_dir),
+            _ => None,
+        }
+    }
+
+    /// The directory user template packs are looked up in. Defaults to a `templates` directory
+    /// next to the user config file.
+    pub fn templates_dir(&self) -> Option<PathBuf> {
+        if !self.templates_dir.is_empty() {
+            return Some(PathBuf::from(&self.templates_dir));
+        }
+
+        user_config_path().and_then(|path| Some(path.parent()?.join("templates")))
+    }
+
     fn default_config() -> Result<Self> {
         let default_config = include_str!("../data/general_info.toml");
 
         Ok(toml::from_str(default_config)?)
     }
 }
+
+impl PartialConfig {
+    /// Reads a layer from disk. A missing file is an empty layer.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let value = match key {
+            "authors" => &self.authors,
+            "cargo_pkg_version" => &self.cargo_pkg_version,
+            "vendor" => &self.vendor,
+            "vendor_url" => &self.vendor_url,
+            "vendor_email" => &self.vendor_email,
+            "nih_plug_git" => &self.nih_plug_git,
+            "templates_dir" => &self.templates_dir,
+            _ => return None,
+        };
+
+        value.as_deref()
+    }
+}
+
+/// Checks that `value` is acceptable for `key`.
+pub fn validate(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "authors" | "vendor" | "templates_dir" => Ok(()),
+        "vendor_url" if value.is_empty() => Ok(()),
+        "vendor_url" => validate_url(value),
+        "vendor_email" if value.is_empty() => Ok(()),
+        "vendor_email" => validate_email(value),
+        "nih_plug_git" => validate_git_url(value),
+        "cargo_pkg_version" => semver::Version::parse(value)
+            .map(|_| ())
+            .map_err(|e| format!("'{}' is not a semver version: {}.", value, e)),
+        _ => Err(format!(
+            "Unknown config key '{}'. Valid keys are: {}.",
+            key,
+            KEYS.join(", ")
+        )),
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+
+    match host {
+        Some(host) if !host.is_empty() && !url.contains(char::is_whitespace) => Ok(()),
+        _ => Err(format!(
+            "'{}' is not a URL. It must start with http:// or https://.",
+            url
+        )),
+    }
+}
+
+fn validate_email(email: &str) -> Result<(), String> {
+    match email.split_once('@') {
+        Some((user, domain))
+            if !user.is_empty()
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && domain.contains('.')
+                && !email.contains(char::is_whitespace) =>
+        {
+            Ok(())
+        }
+        _ => Err(format!(
+            "'{}' is not an email address like name@example.com.",
+            email
+        )),
+    }
+}
+
+fn validate_git_url(url: &str) -> Result<(), String> {
+    if ["ssh://", "git://", "git@", "file://"]
+        .iter()
+        .any(|prefix| url.starts_with(prefix))
+    {
+        return Ok(());
+    }
+
+    validate_url(url)
+}
+
+/// The project-local config file, relative to the current directory.
+pub fn local_config_path() -> PathBuf {
+    PathBuf::from("./data/general_info.toml")
+}
+
+/// `$XDG_CONFIG_HOME/nih-bootstrap/config.toml`, falling back to `~/.config`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(config_dir.join("nih-bootstrap").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(content: &str) -> PartialConfig {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn accepts_email_addresses() {
+        for email in ["name@example.com", "first.last+tag@mail.example.co.uk", ""] {
+            assert!(validate("vendor_email", email).is_ok(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_email_addresses() {
+        for email in [
+            "name",
+            "@example.com",
+            "name@",
+            "name@localhost",
+            "name@.example.com",
+            "name@example.com.",
+            "na me@example.com",
+        ] {
+            assert!(validate("vendor_email", email).is_err(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn accepts_urls() {
+        for url in ["https://example.com", "http://example.com/plugins?id=1", ""] {
+            assert!(validate("vendor_url", url).is_ok(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_urls() {
+        for url in [
+            "example.com",
+            "ftp://example.com",
+            "https://",
+            "https://exa mple.com",
+        ] {
+            assert!(validate("vendor_url", url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn accepts_git_urls() {
+        for url in [
+            "https://github.com/robbert-vdh/nih-plug.git",
+            "git@github.com:robbert-vdh/nih-plug.git",
+            "ssh://git@example.com/nih-plug.git",
+            "git://example.com/nih-plug.git",
+            "file:///home/me/nih-plug",
+        ] {
+            assert!(validate("nih_plug_git", url).is_ok(), "{}", url);
+        }
+
+        assert!(validate("nih_plug_git", "").is_err());
+        assert!(validate("nih_plug_git", "github.com/robbert-vdh/nih-plug").is_err());
+    }
+
+    #[test]
+    fn validates_semver_versions() {
+        for version in ["0.1.0", "1.2.3-beta.1", "2.0.0+build.5"] {
+            assert!(
+                validate("cargo_pkg_version", version).is_ok(),
+                "{}",
+                version
+            );
+        }
+        for version in ["1", "1.2", "v1.2.3", "1.2.3.4", ""] {
+            assert!(
+                validate("cargo_pkg_version", version).is_err(),
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(validate("vendor_mail", "name@example.com").is_err());
+    }
+
+    #[test]
+    fn layer_rejects_misspelled_keys() {
+        let error = toml::from_str::<PartialConfig>("vendor_mail = \"me@example.com\"\n")
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("unknown field `vendor_mail`"), "{}", error);
+    }
+
+    #[test]
+    fn reports_the_layer_each_value_comes_from() {
+        let user = layer("vendor = \"Acme\"\nvendor_url = \"https://acme.example\"\n");
+        let local = layer("vendor_url = \"https://local.example\"\nauthors = \"Me\"\n");
+        let (config, sources) =
+            Config::layered(&[(user, Source::User), (local, Source::Local)]).unwrap();
+
+        assert_eq!(config.vendor, "Acme");
+        assert_eq!(config.vendor_url, "https://local.example");
+        assert_eq!(config.authors, "Me");
+        assert_eq!(config.cargo_pkg_version, "0.1.0");
+
+        let source = |key| sources.iter().find(|(k, _)| *k == key).unwrap().1;
+        assert_eq!(source("vendor"), Source::User);
+        assert_eq!(source("vendor_url"), Source::Local);
+        assert_eq!(source("authors"), Source::Local);
+        assert_eq!(source("cargo_pkg_version"), Source::Default);
+        assert_eq!(sources.len(), KEYS.len());
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 mod commands;
 mod config;
@@ -14,12 +15,22 @@ struct Cli {
     command: Commands,
 }
 
+// parsed once at startup, so the size of the `Init` variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a new plugin project
     Init {
-        /// Project name (kebab-case, snake_case or CamelCase)
-        name: String,
+        /// Project name (kebab-case, snake_case or CamelCase). Starts the wizard when omitted
+        name: Option<String>,
+
+        /// The name hosts show, e.g. "My Cool Plugin". Defaults to the title-cased project name
+        #[arg(long)]
+        display_name: Option<String>,
+
+        /// Ask for every option interactively
+        #[arg(short, long)]
+        interactive: bool,
 
         /// Project Path
         #[arg(short, long, default_value = ".")]
@@ -41,9 +52,66 @@ enum Commands {
         #[arg(short, long)]
         ci: bool,
 
-        /// GUI framework to use (iced, egui, etc.)
-        #[arg(long, default_value = "iced")]
-        gui: String,
+        /// GUI framework to use
+        #[arg(long, value_enum, default_value = "iced")]
+        gui: templates::GuiFramework,
+
+        /// Kind of plugin to start from
+        #[arg(long, value_enum, default_value = "effect")]
+        kind: templates::PluginKind,
+
+        /// Plugin formats to build
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            num_args = 1..,
+            default_value = "vst3,standalone"
+        )]
+        formats: Vec<templates::PluginFormat>,
+
+        /// Use a random VST3 class ID instead of deriving it from the vendor and project name
+        #[arg(long)]
+        random_id: bool,
+
+        /// Template pack to generate from: a directory, or the name of a pack in templates_dir
+        #[arg(long, value_name = "DIR")]
+        template: Option<String>,
+
+        /// Print the files that would be created without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Print the rendered content of a generated file, relative to the project directory,
+        /// without writing anything
+        #[arg(long, value_name = "PATH")]
+        show: Vec<String>,
+
+        /// Overwrite files that already exist in the project directory
+        #[arg(long)]
+        force: bool,
+
+        /// Plugin authors, overriding the configured value
+        #[arg(long)]
+        authors: Option<String>,
+
+        /// Vendor name, overriding the configured value
+        #[arg(long)]
+        vendor: Option<String>,
+
+        /// Vendor URL, overriding the configured value
+        #[arg(long)]
+        vendor_url: Option<String>,
+
+        /// Vendor email, overriding the configured value
+        #[arg(long)]
+        vendor_email: Option<String>,
+    },
+
+    /// Add to an existing project
+    Add {
+        #[command(subcommand)]
+        item: AddItem,
     },
 
     /// Check and install required dependencies
@@ -59,6 +127,129 @@ enum Commands {
         #[arg(short, long)]
         ci: bool,
     },
+
+    /// View and change the bootstrapper configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// List, inspect and export project templates
+    Templates {
+        #[command(subcommand)]
+        action: TemplatesAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum AddItem {
+    /// Add another plugin crate to the workspace
+    Plugin {
+        /// Plugin name (kebab-case, snake_case or CamelCase)
+        name: String,
+
+        /// The name hosts show, e.g. "My Cool Plugin". Defaults to the title-cased plugin name
+        #[arg(long)]
+        display_name: Option<String>,
+
+        /// Directory inside the workspace
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Plugin description
+        #[arg(
+            short,
+            long,
+            default_value = "Rust audio plugin project using nih-plug"
+        )]
+        description: String,
+
+        /// GUI framework to use
+        #[arg(long, value_enum, default_value = "iced")]
+        gui: templates::GuiFramework,
+
+        /// Kind of plugin to start from
+        #[arg(long, value_enum, default_value = "effect")]
+        kind: templates::PluginKind,
+
+        /// Plugin formats to build
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            num_args = 1..,
+            default_value = "vst3,standalone"
+        )]
+        formats: Vec<templates::PluginFormat>,
+
+        /// Use a random VST3 class ID instead of deriving it from the vendor and plugin name
+        #[arg(long)]
+        random_id: bool,
+
+        /// Template pack to generate from: a directory, or the name of a pack in templates_dir
+        #[arg(long, value_name = "DIR")]
+        template: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the value of a config key
+    Get {
+        /// Config key, e.g. vendor or vendor_email
+        key: String,
+    },
+
+    /// Change the value of a config key
+    Set {
+        /// Config key, e.g. vendor or vendor_email
+        key: String,
+
+        /// New value
+        value: String,
+
+        /// Write to the project-local config instead of the user config
+        #[arg(short, long)]
+        local: bool,
+    },
+
+    /// List all config values and the layer they come from
+    List,
+
+    /// Print the path of the config file
+    Path {
+        /// Print the project-local config path instead of the user config path
+        #[arg(short, long)]
+        local: bool,
+    },
+
+    /// Open the config file in $EDITOR
+    Edit {
+        /// Edit the project-local config instead of the user config
+        #[arg(short, long)]
+        local: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplatesAction {
+    /// List the built-in templates and the user template packs
+    List,
+
+    /// Show the files and variables of a template pack
+    Show {
+        /// Pack name or directory, "builtin", or a GUI framework or plugin kind
+        name: String,
+    },
+
+    /// Copy a template pack into a directory as an editable pack
+    Export {
+        /// Pack name or directory, "builtin", or a GUI framework or plugin kind
+        name: String,
+
+        /// Directory to export to
+        dir: PathBuf,
+    },
 }
 
 #[tokio::main]
@@ -68,20 +259,106 @@ async fn main() -> Result<()> {
     match &cli.command {
         Commands::Init {
             name,
+            display_name,
+            interactive,
             path,
             description,
             git,
             ci,
             gui,
+            kind,
+            formats,
+            random_id,
+            template,
+            dry_run,
+            show,
+            force,
+            authors,
+            vendor,
+            vendor_url,
+            vendor_email,
         } => {
-            commands::init::run(name, path, description, *git, *ci, gui).await?;
+            let mut options = commands::init::InitOptions {
+                name: name.clone().unwrap_or_default(),
+                display_name: display_name.clone(),
+                path: path.clone(),
+                description: description.clone(),
+                init_git: *git,
+                init_ci: *ci,
+                gui: *gui,
+                kind: *kind,
+                formats: formats.clone(),
+                random_id: *random_id,
+                template: template.clone(),
+                dry_run: *dry_run,
+                show: show.clone(),
+                force: *force,
+                overrides: config::PartialConfig {
+                    authors: authors.clone(),
+                    vendor: vendor.clone(),
+                    vendor_url: vendor_url.clone(),
+                    vendor_email: vendor_email.clone(),
+                    ..Default::default()
+                },
+            };
+
+            if name.is_none() || *interactive {
+                options = commands::wizard::run(options)?;
+            }
+
+            commands::init::run(&options).await?;
         }
+        Commands::Add { item } => match item {
+            AddItem::Plugin {
+                name,
+                display_name,
+                path,
+                description,
+                gui,
+                kind,
+                formats,
+                random_id,
+                template,
+            } => {
+                let options = commands::init::InitOptions {
+                    name: name.clone(),
+                    display_name: display_name.clone(),
+                    path: path.clone(),
+                    description: description.clone(),
+                    init_git: false,
+                    init_ci: false,
+                    gui: *gui,
+                    kind: *kind,
+                    formats: formats.clone(),
+                    random_id: *random_id,
+                    template: template.clone(),
+                    dry_run: false,
+                    show: Vec::new(),
+                    force: false,
+                    overrides: config::PartialConfig::default(),
+                };
+
+                commands::add::plugin(&options)?;
+            }
+        },
         Commands::Deps { install } => {
             commands::dependencies::check_and_install(*install).await?;
         }
         Commands::Git { ci } => {
             commands::git::setup(*ci).await?;
         }
+        Commands::Config { action } => match action {
+            ConfigAction::Get { key } => commands::config::get(key)?,
+            ConfigAction::Set { key, value, local } => commands::config::set(key, value, *local)?,
+            ConfigAction::List => commands::config::list()?,
+            ConfigAction::Path { local } => commands::config::path(*local)?,
+            ConfigAction::Edit { local } => commands::config::edit(*local)?,
+        },
+        Commands::Templates { action } => match action {
+            TemplatesAction::List => commands::templates::list()?,
+            TemplatesAction::Show { name } => commands::templates::show(name)?,
+            TemplatesAction::Export { name, dir } => commands::templates::export(name, dir)?,
+        },
     }
 
     Ok(())
@@ -0,0 +1,31 @@
+use super::CommandRunner;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Answers commands from a table and records the ones that were run.
+#[derive(Default)]
+pub struct FakeRunner {
+    pub outputs: HashMap<String, String>,
+    pub ran: RefCell<Vec<String>>,
+}
+
+impl FakeRunner {
+    pub fn with(mut self, command: &str, output: &str) -> Self {
+        self.outputs.insert(command.to_string(), output.to_string());
+        self
+    }
+}
+
+impl CommandRunner for FakeRunner {
+    fn output(&self, program: &str, args: &[&str]) -> Option<String> {
+        let command = [&[program], args].concat().join(" ");
+        self.outputs.get(&command).cloned()
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> anyhow::Result<bool> {
+        self.ran
+            .borrow_mut()
+            .push([&[program], args].concat().join(" "));
+        Ok(true)
+    }
+}
@@ -1,123 +1,759 @@
-use super::Platform;
-use crate::commands::dependencies::Dependency;
+use super::{CommandRunner, Platform, RUSTUP_INSTALLER, SystemRunner};
+use crate::commands::dependencies::{Dependency, Installed, Report};
 use anyhow::Result;
-use std::process::Command;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 
-pub struct Linux;
+/// A library nih-plug needs, with its pkg-config modules and the packages that provide it on each
+/// distribution family.
+struct Library {
+    info: &'static str,
+    pkg_config: &'static [&'static str],
+    apt: &'static [&'static str],
+    dnf: &'static [&'static str],
+    pacman: &'static [&'static str],
+    zypper: &'static [&'static str],
+    apk: &'static [&'static str],
+}
+
+const LIBRARIES: &[Library] = &[
+    Library {
+        info: "ALSA, audio and MIDI for the standalone",
+        pkg_config: &["alsa"],
+        apt: &["libasound2-dev"],
+        dnf: &["alsa-lib-devel"],
+        pacman: &["alsa-lib"],
+        zypper: &["alsa-devel"],
+        apk: &["alsa-lib-dev"],
+    },
+    Library {
+        info: "JACK Audio Connection Kit",
+        pkg_config: &["jack"],
+        apt: &["libjack-jackd2-dev"],
+        dnf: &["pipewire-jack-audio-connection-kit-devel"],
+        pacman: &["jack2"],
+        zypper: &["libjack-devel"],
+        apk: &["jack-dev"],
+    },
+    Library {
+        info: "X11 client library",
+        pkg_config: &["x11"],
+        apt: &["libx11-dev"],
+        dnf: &["libX11-devel"],
+        pacman: &["libx11"],
+        zypper: &["libX11-devel"],
+        apk: &["libx11-dev"],
+    },
+    Library {
+        info: "XCB interface of the X11 client library",
+        pkg_config: &["x11-xcb"],
+        apt: &["libx11-xcb-dev"],
+        dnf: &["libX11-devel"],
+        pacman: &["libx11"],
+        zypper: &["libX11-devel"],
+        apk: &["libx11-dev"],
+    },
+    Library {
+        info: "X C Binding",
+        pkg_config: &["xcb"],
+        apt: &["libxcb1-dev"],
+        dnf: &["libxcb-devel"],
+        pacman: &["libxcb"],
+        zypper: &["libxcb-devel"],
+        apk: &["libxcb-dev"],
+    },
+    Library {
+        info: "XCB render extension",
+        pkg_config: &["xcb-render"],
+        apt: &["libxcb-render0-dev"],
+        dnf: &["libxcb-devel"],
+        pacman: &["libxcb"],
+        zypper: &["libxcb-devel"],
+        apk: &["libxcb-dev"],
+    },
+    Library {
+        info: "XCB shape extension",
+        pkg_config: &["xcb-shape"],
+        apt: &["libxcb-shape0-dev"],
+        dnf: &["libxcb-devel"],
+        pacman: &["libxcb"],
+        zypper: &["libxcb-devel"],
+        apk: &["libxcb-dev"],
+    },
+    Library {
+        info: "XCB xfixes extension",
+        pkg_config: &["xcb-xfixes"],
+        apt: &["libxcb-xfixes0-dev"],
+        dnf: &["libxcb-devel"],
+        pacman: &["libxcb"],
+        zypper: &["libxcb-devel"],
+        apk: &["libxcb-dev"],
+    },
+    Library {
+        info: "XCB dri2 extension",
+        pkg_config: &["xcb-dri2"],
+        apt: &["libxcb-dri2-0-dev"],
+        dnf: &["libxcb-devel"],
+        pacman: &["libxcb"],
+        zypper: &["libxcb-devel"],
+        apk: &["libxcb-dev"],
+    },
+    Library {
+        info: "XCB utility libraries",
+        pkg_config: &["xcb-util"],
+        apt: &["libxcb-util-dev"],
+        dnf: &["xcb-util-devel"],
+        pacman: &["xcb-util"],
+        zypper: &["xcb-util-devel"],
+        apk: &["xcb-util-dev"],
+    },
+    Library {
+        info: "XCB ICCCM helpers for window management",
+        pkg_config: &["xcb-icccm"],
+        apt: &["libxcb-icccm4-dev"],
+        dnf: &["xcb-util-wm-devel"],
+        pacman: &["xcb-util-wm"],
+        zypper: &["xcb-util-wm-devel"],
+        apk: &["xcb-util-wm-dev"],
+    },
+    Library {
+        info: "OpenGL, used by the GUI frameworks",
+        pkg_config: &["gl"],
+        apt: &["libgl1-mesa-dev", "mesa-common-dev"],
+        dnf: &["mesa-libGL-devel"],
+        pacman: &["mesa"],
+        zypper: &["Mesa-libGL-devel"],
+        apk: &["mesa-dev"],
+    },
+    Library {
+        info: "X cursor management library",
+        pkg_config: &["xcursor"],
+        apt: &["libxcursor-dev"],
+        dnf: &["libXcursor-devel"],
+        pacman: &["libxcursor"],
+        zypper: &["libXcursor-devel"],
+        apk: &["libxcursor-dev"],
+    },
+    Library {
+        info: "pkg-config, finds the libraries at build time",
+        pkg_config: &[],
+        apt: &["pkg-config"],
+        dnf: &["pkgconf-pkg-config"],
+        pacman: &["pkgconf"],
+        zypper: &["pkg-config"],
+        apk: &["pkgconf"],
+    },
+];
+
+/// The package manager families `deps` knows the package names for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+    Zypper,
+    Apk,
+}
+
+impl PackageManager {
+    /// Picks the package manager from the `ID` and `ID_LIKE` fields of an os-release file.
+    fn from_os_release(content: &str) -> Option<Self> {
+        let fields = parse_os_release(content);
+        let mut ids = fields
+            .get("ID")
+            .into_iter()
+            .chain(fields.get("ID_LIKE"))
+            .flat_map(|ids| ids.split_whitespace());
+
+        ids.find_map(|id| match id {
+            "debian" | "ubuntu" | "linuxmint" | "pop" | "elementary" | "raspbian" => {
+                Some(PackageManager::Apt)
+            }
+            "fedora" | "rhel" | "centos" | "rocky" | "almalinux" | "nobara" => {
+                Some(PackageManager::Dnf)
+            }
+            "arch" | "manjaro" | "endeavouros" | "cachyos" => Some(PackageManager::Pacman),
+            "suse" | "opensuse" | "opensuse-leap" | "opensuse-tumbleweed" | "sles" => {
+                Some(PackageManager::Zypper)
+            }
+            "alpine" => Some(PackageManager::Apk),
+            _ => None,
+        })
+    }
+
+    fn packages(self, library: &Library) -> &'static [&'static str] {
+        match self {
+            PackageManager::Apt => library.apt,
+            PackageManager::Dnf => library.dnf,
+            PackageManager::Pacman => library.pacman,
+            PackageManager::Zypper => library.zypper,
+            PackageManager::Apk => library.apk,
+        }
+    }
+
+    /// The command that succeeds when `package` is installed, and its expected output.
+    fn query(self, package: &str) -> (&'static str, Vec<&str>, Option<&'static str>) {
+        match self {
+            // `dpkg -l` also succeeds for removed packages that left their config behind
+            PackageManager::Apt => (
+                "dpkg-query",
+                vec!["-W", "-f=${Status}", package],
+                Some("install ok installed"),
+            ),
+            PackageManager::Dnf | PackageManager::Zypper => ("rpm", vec!["-q", package], None),
+            // checks the dependency, so a package that provides it counts as well
+            PackageManager::Pacman => ("pacman", vec!["-T", package], None),
+            PackageManager::Apk => ("apk", vec!["info", "-e", package], None),
+        }
+    }
+
+    /// The commands that install `packages`, all run with sudo.
+    fn install_commands<'a>(self, packages: &[&'a str]) -> Vec<Vec<&'a str>> {
+        let install = match self {
+            PackageManager::Apt => vec!["apt-get", "install", "-y"],
+            PackageManager::Dnf => vec!["dnf", "install", "-y"],
+            PackageManager::Pacman => vec!["pacman", "-S", "--needed", "--noconfirm"],
+            PackageManager::Zypper => vec!["zypper", "--non-interactive", "install"],
+            PackageManager::Apk => vec!["apk", "add"],
+        };
+
+        let mut commands = Vec::new();
+        if self == PackageManager::Apt {
+            commands.push(vec!["apt-get", "update"]);
+        }
+        commands.push(
+            install
+                .into_iter()
+                .chain(packages.iter().copied())
+                .collect(),
+        );
+
+        commands
+    }
+}
+
+pub struct Linux<R: CommandRunner = SystemRunner> {
+    runner: R,
+    os_release: PathBuf,
+}
+
+impl Default for Linux {
+    fn default() -> Self {
+        // /usr/lib/os-release is the fallback when /etc doesn't have one
+        let os_release = ["/etc/os-release", "/usr/lib/os-release"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .unwrap_or_else(|| PathBuf::from("/etc/os-release"));
+
+        Self {
+            runner: SystemRunner,
+            os_release,
+        }
+    }
+}
+
+impl<R: CommandRunner> Linux<R> {
+    #[cfg(test)]
+    fn with_runner(runner: R, os_release: PathBuf) -> Self {
+        Self { runner, os_release }
+    }
+
+    fn package_manager(&self) -> Result<PackageManager> {
+        let content = fs::read_to_string(&self.os_release).unwrap_or_default();
+        let fields = parse_os_release(&content);
+
+        PackageManager::from_os_release(&content).ok_or_else(|| {
+            let name = fields
+                .get("PRETTY_NAME")
+                .or_else(|| fields.get("ID"))
+                .map(String::as_str)
+                .unwrap_or("this distribution");
+            let libraries: Vec<_> = LIBRARIES.iter().map(|library| library.info).collect();
+
+            anyhow::anyhow!(
+                "nih-bootstrap doesn't know the package names for {}. Install the development packages for:\n  {}",
+                name,
+                libraries.join("\n  ")
+            )
+        })
+    }
 
-impl Platform for Linux {
+    /// The version of a pkg-config module, if it is installed.
+    fn module_version(&self, module: &str) -> Option<String> {
+        self.runner.output("pkg-config", &["--exists", module])?;
+
+        Some(
+            self.runner
+                .output("pkg-config", &["--modversion", module])
+                .map(|version| version.trim().to_string())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The packages of `libraries` that the package manager doesn't report as installed.
+    fn missing_packages(&self, manager: PackageManager, libraries: &[&Library]) -> Vec<Dependency> {
+        let mut missing: Vec<Dependency> = Vec::new();
+
+        for library in libraries {
+            for package in manager.packages(library) {
+                // several libraries share a package on most distributions
+                if missing.iter().any(|dep| dep.name == *package) {
+                    continue;
+                }
+
+                if !self.is_installed(manager, package) {
+                    missing.push(Dependency {
+                        name: package,
+                        info: library.info,
+                    });
+                }
+            }
+        }
+
+        missing
+    }
+
+    fn is_installed(&self, manager: PackageManager, package: &str) -> bool {
+        let (program, args, expected) = manager.query(package);
+
+        match (self.runner.output(program, &args), expected) {
+            (Some(output), Some(expected)) => output.contains(expected),
+            (output, None) => output.is_some(),
+            (None, _) => false,
+        }
+    }
+}
+
+impl<R: CommandRunner> Platform for Linux<R> {
     fn name(&self) -> &'static str {
         "Linux"
     }
 
-    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
-        let mut missing = Vec::new();
-
-        let deps = vec![
-            Dependency {
-                name: "libasound2-dev",
-                info: "shared library for ALSA applications -- development files",
-            },
-            Dependency {
-                name: "libjack-jackd2-dev",
-                info: "JACK Audio Connection Kit (development files)",
-            },
-            Dependency {
-                name: "libx11-dev",
-                info: "X11 client-side library (development headers)",
-            },
-            Dependency {
-                name: "libx11-xcb-dev",
-                info: "Xlib/XCB interface library (development headers)",
-            },
-            Dependency {
-                name: "libxcb1-dev",
-                info: "X C Binding, development files",
-            },
-            Dependency {
-                name: "libxcb-util-dev",
-                info: "utility libraries for X C Binding -- atom, aux and event",
-            },
-            Dependency {
-                name: "libxcb-render0-dev",
-                info: "X C Binding, render extension, development files",
-            },
-            Dependency {
-                name: "libxcb-shape0-dev",
-                info: "X C Binding, shape extension, development files",
-            },
-            Dependency {
-                name: "libxcb-xfixes0-dev",
-                info: "X C Binding, xfixes extension, development files",
-            },
-            Dependency {
-                name: "libxcb-icccm4-dev",
-                info: "utility libraries for X C Binding -- icccm, development files",
-            },
-            Dependency {
-                name: "libxcb-dri2-0-dev",
-                info: "lX C Binding, dri2 extension, development files",
-            },
-            Dependency {
-                name: "mesa-common-dev",
-                info: "Developer documentation for Mesa",
-            },
-            Dependency {
-                name: "libgl1-mesa-dev",
-                info: "transitional dummy package",
-            },
-            Dependency {
-                name: "libxcursor-dev",
-                info: "X cursor management library (development files)",
-            },
-            Dependency {
-                name: "pkg-config",
-                info: "manage compile and link flags for libraries (transitional package)",
-            },
-        ];
-
-        for dep in deps {
-            let output = Command::new("dpkg").args(["-l", dep.name]).output();
-
-            if let Ok(output) = output {
-                if !output.status.success() {
-                    missing.push(dep);
+    async fn check_dependencies(&self) -> Result<Report> {
+        let manager = self.package_manager();
+
+        let Some(pkg_config_version) = self.runner.output("pkg-config", &["--version"]) else {
+            // nothing can be checked without pkg-config, ask the package manager instead
+            let libraries: Vec<_> = LIBRARIES.iter().collect();
+
+            return Ok(Report {
+                missing: self.missing_packages(manager?, &libraries),
+                ..Default::default()
+            });
+        };
+
+        let mut report = Report::default();
+        report.installed.push(Installed {
+            name: "pkg-config".to_string(),
+            version: pkg_config_version.trim().to_string(),
+        });
+
+        let mut missing_modules = Vec::new();
+        for library in LIBRARIES {
+            for module in library.pkg_config {
+                match self.module_version(module) {
+                    Some(version) => report.installed.push(Installed {
+                        name: module.to_string(),
+                        version,
+                    }),
+                    None => missing_modules.push((library, *module)),
                 }
             }
         }
 
-        Ok(missing)
-    }
+        let Ok(manager) = manager else {
+            // without package names the pkg-config modules are the best hint
+            report.missing = missing_modules
+                .into_iter()
+                .map(|(library, module)| Dependency {
+                    name: module,
+                    info: library.info,
+                })
+                .collect();
 
-    async fn install_dependencies(&self, dependencies: &[Dependency]) -> anyhow::Result<()> {
-        let status = Command::new("sudo").args(["apt-get", "update"]).status()?;
+            return Ok(report);
+        };
 
-        if !status.success() {
-            anyhow::bail!("Failed to update package lists.");
+        let mut libraries: Vec<&Library> = Vec::new();
+        for (library, _) in missing_modules {
+            if !libraries.iter().any(|known| std::ptr::eq(*known, library)) {
+                libraries.push(library);
+            }
         }
 
-        let status = Command::new("sudo")
-            .args(["apt-get", "install", "-y"])
-            .args(dependencies.iter().map(|dep| dep.name))
-            .status()?;
+        for library in libraries {
+            let mut packages = self.missing_packages(manager, &[library]);
+            if packages.is_empty() {
+                // pkg-config decides, the packages may be installed but broken or found under
+                // another name
+                packages = manager
+                    .packages(library)
+                    .iter()
+                    .map(|package| Dependency {
+                        name: package,
+                        info: library.info,
+                    })
+                    .collect();
+            }
+
+            for package in packages {
+                if !report.missing.iter().any(|dep| dep.name == package.name) {
+                    report.missing.push(package);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn install_dependencies(&self, dependencies: &[Dependency]) -> anyhow::Result<()> {
+        let manager = self.package_manager()?;
+        let packages: Vec<_> = dependencies.iter().map(|dep| dep.name).collect();
 
-        if !status.success() {
-            anyhow::bail!("Failed to install dependencies.");
+        for command in manager.install_commands(&packages) {
+            if !self.runner.run("sudo", &command)? {
+                anyhow::bail!("Failed to run: sudo {}", command.join(" "));
+            }
         }
 
         Ok(())
     }
 
     fn install_instructions(&self, dependencies: &[Dependency]) -> String {
-        let mut deps = Vec::new();
+        let packages: Vec<_> = dependencies.iter().map(|dep| dep.name).collect();
+
+        match self.package_manager() {
+            Ok(manager) => {
+                let commands: Vec<_> = manager
+                    .install_commands(&packages)
+                    .iter()
+                    .map(|command| format!("sudo {}", command.join(" ")))
+                    .collect();
+
+                commands.join(" && ")
+            }
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn rustup_install(&self) -> Vec<Vec<&'static str>> {
+        let content = fs::read_to_string(&self.os_release).unwrap_or_default();
+        let is_fedora = parse_os_release(&content)
+            .get("ID")
+            .is_some_and(|id| id == "fedora");
+
+        // the packaged rustup still has to install a toolchain. RHEL and its rebuilds don't
+        // package rustup at all
+        let setup = match PackageManager::from_os_release(&content) {
+            Some(manager @ (PackageManager::Pacman | PackageManager::Zypper)) => {
+                Some((manager, vec!["rustup", "default", "stable"]))
+            }
+            Some(manager @ PackageManager::Apk) => Some((manager, vec!["rustup-init", "-y"])),
+            Some(manager @ PackageManager::Dnf) if is_fedora => {
+                Some((manager, vec!["rustup-init", "-y"]))
+            }
+            _ => None,
+        };
 
-        for dep in dependencies {
-            deps.push(dep.name);
+        match setup {
+            Some((manager, setup)) => manager
+                .install_commands(&["rustup"])
+                .into_iter()
+                .map(|command| [vec!["sudo"], command].concat())
+                .chain([setup])
+                .collect(),
+            None => vec![vec!["sh", "-c", RUSTUP_INSTALLER]],
         }
+    }
+}
+
+/// The `KEY=value` fields of an os-release file, with quotes removed.
+fn parse_os_release(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::FakeRunner;
+    use std::path::Path;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/os-release")
+            .join(name)
+    }
 
-        format!(
-            "sudo apt-get update && sudo apt-get install -y {}",
-            deps.join(" "),
+    fn manager(name: &str) -> Option<PackageManager> {
+        PackageManager::from_os_release(&fs::read_to_string(fixture(name)).unwrap())
+    }
+
+    async fn check(name: &str, runner: FakeRunner) -> (Vec<&'static str>, String) {
+        let linux = Linux::with_runner(runner, fixture(name));
+        let missing = linux.check_dependencies().await.unwrap().missing;
+
+        (
+            missing.iter().map(|dep| dep.name).collect(),
+            linux.install_instructions(&missing),
         )
     }
+
+    #[test]
+    fn detects_package_manager() {
+        assert_eq!(manager("debian"), Some(PackageManager::Apt));
+        assert_eq!(manager("ubuntu"), Some(PackageManager::Apt));
+        assert_eq!(manager("fedora"), Some(PackageManager::Dnf));
+        assert_eq!(manager("rocky"), Some(PackageManager::Dnf));
+        assert_eq!(manager("arch"), Some(PackageManager::Pacman));
+        assert_eq!(manager("endeavouros"), Some(PackageManager::Pacman));
+        assert_eq!(manager("opensuse-tumbleweed"), Some(PackageManager::Zypper));
+        assert_eq!(manager("alpine"), Some(PackageManager::Apk));
+        assert_eq!(manager("nixos"), None);
+    }
+
+    #[test]
+    fn rustup_comes_from_the_package_manager_where_packaged() {
+        let rustup_install =
+            |name| Linux::with_runner(FakeRunner::default(), fixture(name)).rustup_install();
+
+        assert_eq!(
+            rustup_install("arch"),
+            [
+                vec!["sudo", "pacman", "-S", "--needed", "--noconfirm", "rustup"],
+                vec!["rustup", "default", "stable"]
+            ]
+        );
+        assert_eq!(
+            rustup_install("fedora"),
+            [
+                vec!["sudo", "dnf", "install", "-y", "rustup"],
+                vec!["rustup-init", "-y"]
+            ]
+        );
+        assert_eq!(
+            rustup_install("alpine"),
+            [
+                vec!["sudo", "apk", "add", "rustup"],
+                vec!["rustup-init", "-y"]
+            ]
+        );
+        for name in ["debian", "rocky", "nixos"] {
+            assert_eq!(
+                rustup_install(name),
+                [vec!["sh", "-c", RUSTUP_INSTALLER]],
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn parses_quoted_values() {
+        let fields = parse_os_release(&fs::read_to_string(fixture("rocky")).unwrap());
+
+        assert_eq!(fields["ID"], "rocky");
+        assert_eq!(fields["ID_LIKE"], "rhel centos fedora");
+        assert_eq!(fields["PRETTY_NAME"], "Rocky Linux 9.6 (Blue Onyx)");
+    }
+
+    #[tokio::test]
+    async fn debian_checks_dpkg_status() {
+        let runner = FakeRunner::default()
+            .with(
+                "dpkg-query -W -f=${Status} libasound2-dev",
+                "install ok installed",
+            )
+            // removed, only the config files are left
+            .with(
+                "dpkg-query -W -f=${Status} libjack-jackd2-dev",
+                "deinstall ok config-files",
+            );
+        let (missing, instructions) = check("debian", runner).await;
+
+        assert_eq!(missing.len(), 14);
+        assert!(!missing.contains(&"libasound2-dev"));
+        assert!(missing.contains(&"libjack-jackd2-dev"));
+        assert!(instructions.starts_with(
+            "sudo apt-get update && sudo apt-get install -y libjack-jackd2-dev libx11-dev"
+        ));
+    }
+
+    #[tokio::test]
+    async fn fedora_uses_dnf() {
+        let runner = FakeRunner::default().with("rpm -q alsa-lib-devel", "alsa-lib-devel-1.2.14");
+        let (missing, instructions) = check("fedora", runner).await;
+
+        assert_eq!(
+            instructions,
+            "sudo dnf install -y pipewire-jack-audio-connection-kit-devel libX11-devel libxcb-devel \
+             xcb-util-devel xcb-util-wm-devel mesa-libGL-devel libXcursor-devel pkgconf-pkg-config"
+        );
+        assert!(!missing.contains(&"alsa-lib-devel"));
+    }
+
+    #[tokio::test]
+    async fn arch_uses_pacman() {
+        let (missing, instructions) = check("endeavouros", FakeRunner::default()).await;
+
+        assert_eq!(
+            missing,
+            [
+                "alsa-lib",
+                "jack2",
+                "libx11",
+                "libxcb",
+                "xcb-util",
+                "xcb-util-wm",
+                "mesa",
+                "libxcursor",
+                "pkgconf"
+            ]
+        );
+        assert!(instructions.starts_with("sudo pacman -S --needed --noconfirm alsa-lib jack2"));
+    }
+
+    #[tokio::test]
+    async fn opensuse_uses_zypper() {
+        let (_, instructions) = check("opensuse-tumbleweed", FakeRunner::default()).await;
+
+        assert!(instructions.starts_with("sudo zypper --non-interactive install alsa-devel"));
+    }
+
+    #[tokio::test]
+    async fn alpine_uses_apk() {
+        let (_, instructions) = check("alpine", FakeRunner::default()).await;
+
+        assert!(instructions.starts_with("sudo apk add alsa-lib-dev jack-dev"));
+    }
+
+    #[tokio::test]
+    async fn unknown_distribution_lists_the_libraries() {
+        let linux = Linux::with_runner(FakeRunner::default(), fixture("nixos"));
+        let error = linux.check_dependencies().await.unwrap_err().to_string();
+
+        assert!(error.contains("NixOS 25.05 (Warbler)"), "{}", error);
+        assert!(error.contains("JACK Audio Connection Kit"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn install_runs_with_sudo() {
+        let linux = Linux::with_runner(FakeRunner::default(), fixture("ubuntu"));
+        let missing = [Dependency {
+            name: "libasound2-dev",
+            info: "",
+        }];
+        linux.install_dependencies(&missing).await.unwrap();
+
+        assert_eq!(
+            *linux.runner.ran.borrow(),
+            [
+                "sudo apt-get update",
+                "sudo apt-get install -y libasound2-dev"
+            ]
+        );
+    }
+
+    /// Adds pkg-config with every module installed except `missing`.
+    fn with_pkg_config(runner: FakeRunner, missing: &[&str]) -> FakeRunner {
+        let mut runner = runner.with("pkg-config --version", "1.8.1\n");
+
+        for module in LIBRARIES.iter().flat_map(|library| library.pkg_config) {
+            if !missing.contains(module) {
+                runner = runner
+                    .with(&format!("pkg-config --exists {}", module), "")
+                    .with(&format!("pkg-config --modversion {}", module), "1.2.3\n");
+            }
+        }
+
+        runner
+    }
+
+    #[tokio::test]
+    async fn pkg_config_reports_versions() {
+        let runner = with_pkg_config(FakeRunner::default(), &[])
+            .with("pkg-config --modversion alsa", "1.2.11\n");
+        let linux = Linux::with_runner(runner, fixture("ubuntu"));
+        let report = linux.check_dependencies().await.unwrap();
+
+        assert!(report.missing.is_empty());
+        assert_eq!(report.installed.len(), 14);
+        assert!(report.installed.contains(&Installed {
+            name: "alsa".to_string(),
+            version: "1.2.11".to_string(),
+        }));
+        assert_eq!(report.installed[0].name, "pkg-config");
+    }
+
+    #[tokio::test]
+    async fn pkg_config_modules_are_named_by_package() {
+        let runner = with_pkg_config(FakeRunner::default(), &["jack", "xcb-shape", "xcb-dri2"]);
+        let (missing, instructions) = check("ubuntu", runner).await;
+
+        // only the packages of modules pkg-config can't find are queried
+        assert_eq!(
+            missing,
+            [
+                "libjack-jackd2-dev",
+                "libxcb-shape0-dev",
+                "libxcb-dri2-0-dev"
+            ]
+        );
+        assert_eq!(
+            instructions,
+            "sudo apt-get update && sudo apt-get install -y libjack-jackd2-dev libxcb-shape0-dev \
+             libxcb-dri2-0-dev"
+        );
+    }
+
+    #[tokio::test]
+    async fn pkg_config_wins_over_the_package_manager() {
+        // the package is installed, but pkg-config can't find the library
+        let runner = with_pkg_config(FakeRunner::default(), &["jack"]).with(
+            "rpm -q pipewire-jack-audio-connection-kit-devel",
+            "pipewire-jack-audio-connection-kit-devel-1.4.8",
+        );
+        let (missing, _) = check("fedora", runner).await;
+        assert_eq!(missing, ["pipewire-jack-audio-connection-kit-devel"]);
+
+        // pipewire-jack provides the library without the jack2 package
+        let runner = with_pkg_config(FakeRunner::default(), &[]);
+        let (missing, _) = check("arch", runner).await;
+        assert!(missing.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_distribution_with_pkg_config_names_modules() {
+        let runner = with_pkg_config(FakeRunner::default(), &["alsa", "gl"]);
+        let linux = Linux::with_runner(runner, fixture("nixos"));
+        let report = linux.check_dependencies().await.unwrap();
+        let missing: Vec<_> = report.missing.iter().map(|dep| dep.name).collect();
+
+        assert_eq!(missing, ["alsa", "gl"]);
+        assert!(
+            linux
+                .install_instructions(&report.missing)
+                .contains("NixOS 25.05 (Warbler)")
+        );
+    }
 }
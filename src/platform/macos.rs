@@ -1 +1,144 @@
+use super::{CommandRunner, Platform, RUSTUP_INSTALLER, SystemRunner};
+use crate::commands::dependencies::{Dependency, Report};
+use anyhow::Result;
 
+const COMMAND_LINE_TOOLS: Dependency = Dependency {
+    name: "Xcode Command Line Tools",
+    info: "clang, the linker and the macOS SDK",
+};
+
+/// Both targets are needed to build universal bundles with `cargo xtask bundle-universal`.
+const TARGETS: &[Dependency] = &[
+    Dependency {
+        name: "x86_64-apple-darwin",
+        info: "Rust target for Intel Macs",
+    },
+    Dependency {
+        name: "aarch64-apple-darwin",
+        info: "Rust target for Apple silicon Macs",
+    },
+];
+
+#[derive(Default)]
+pub struct MacOS<R: CommandRunner = SystemRunner> {
+    runner: R,
+}
+
+impl<R: CommandRunner> MacOS<R> {
+    #[cfg(test)]
+    fn with_runner(runner: R) -> Self {
+        Self { runner }
+    }
+
+    fn has_command_line_tools(&self) -> bool {
+        // prints the developer directory, fails when the tools were never installed
+        self.runner.output("xcode-select", &["-p"]).is_some()
+    }
+}
+
+impl<R: CommandRunner> Platform for MacOS<R> {
+    fn name(&self) -> &'static str {
+        "macOS"
+    }
+
+    async fn check_dependencies(&self) -> Result<Report> {
+        let mut missing = Vec::new();
+
+        if !self.has_command_line_tools() {
+            missing.push(COMMAND_LINE_TOOLS);
+        }
+
+        Ok(Report {
+            missing,
+            ..Default::default()
+        })
+    }
+
+    async fn install_dependencies(&self, dependencies: &[Dependency]) -> Result<()> {
+        if dependencies.contains(&COMMAND_LINE_TOOLS) {
+            // only opens the installer dialog, the installation continues in the background
+            if !self.runner.run("xcode-select", &["--install"])? {
+                anyhow::bail!("Failed to start the Xcode Command Line Tools installer.");
+            }
+            println!("Finish the Xcode Command Line Tools installation in the dialog that opened.");
+        }
+
+        Ok(())
+    }
+
+    fn install_instructions(&self, dependencies: &[Dependency]) -> String {
+        let mut commands = Vec::new();
+
+        if dependencies.contains(&COMMAND_LINE_TOOLS) {
+            commands.push("xcode-select --install".to_string());
+        }
+
+        commands.join("\n")
+    }
+
+    fn rust_targets(&self) -> &'static [Dependency] {
+        TARGETS
+    }
+
+    /// Through Homebrew when it is installed, with the official installer otherwise.
+    fn rustup_install(&self) -> Vec<Vec<&'static str>> {
+        if self.runner.output("brew", &["--version"]).is_some() {
+            vec![vec!["brew", "install", "rustup"], vec!["rustup-init", "-y"]]
+        } else {
+            vec![vec!["sh", "-c", RUSTUP_INSTALLER]]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::FakeRunner;
+
+    #[tokio::test]
+    async fn command_line_tools_are_found() {
+        let runner =
+            FakeRunner::default().with("xcode-select -p", "/Library/Developer/CommandLineTools\n");
+        let macos = MacOS::with_runner(runner);
+
+        assert!(macos.check_dependencies().await.unwrap().missing.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_command_line_tools_are_installed_with_xcode_select() {
+        let macos = MacOS::with_runner(FakeRunner::default());
+        let missing = macos.check_dependencies().await.unwrap().missing;
+
+        assert_eq!(missing, [COMMAND_LINE_TOOLS]);
+        assert_eq!(
+            macos.install_instructions(&missing),
+            "xcode-select --install"
+        );
+
+        macos.install_dependencies(&missing).await.unwrap();
+        assert_eq!(*macos.runner.ran.borrow(), ["xcode-select --install"]);
+    }
+
+    #[test]
+    fn universal_bundles_need_both_targets() {
+        let names: Vec<_> = MacOS::with_runner(FakeRunner::default())
+            .rust_targets()
+            .iter()
+            .map(|target| target.name)
+            .collect();
+
+        assert_eq!(names, ["x86_64-apple-darwin", "aarch64-apple-darwin"]);
+    }
+
+    #[test]
+    fn rustup_comes_from_homebrew_when_available() {
+        let runner = FakeRunner::default().with("brew --version", "Homebrew 4.6.0\n");
+        assert_eq!(
+            MacOS::with_runner(runner).rustup_install(),
+            [vec!["brew", "install", "rustup"], vec!["rustup-init", "-y"]]
+        );
+
+        let macos = MacOS::with_runner(FakeRunner::default());
+        assert_eq!(macos.rustup_install()[0][0], "sh");
+    }
+}
@@ -3,28 +3,112 @@ mod linux;
 #[cfg(target_os = "linux")]
 pub use linux::Linux as CurrentPlatform;
 
-#[cfg(target_os = "macos")]
+// the macOS and Windows backends are built on every OS so their checks can be unit tested
+// anywhere
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
 mod macos;
 #[cfg(target_os = "macos")]
 pub use macos::MacOS as CurrentPlatform;
 
-#[cfg(target_os = "windows")]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::Windows as CurrentPlatform;
 
-use crate::commands::dependencies::Dependency;
+pub mod toolchain;
+
+#[cfg(test)]
+mod fake;
+#[cfg(test)]
+pub use fake::FakeRunner;
+
+use crate::commands::dependencies::{Dependency, Report};
+use std::process::{Command, Stdio};
+
+/// The official rustup installer, for platforms without a package that provides rustup.
+pub const RUSTUP_INSTALLER: &str =
+    "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y";
+
+/// The official rustup-init.exe, downloaded and run with PowerShell on Windows without winget or
+/// Chocolatey.
+pub const RUSTUP_INIT_DOWNLOAD: &str = r"Invoke-WebRequest https://win.rustup.rs/x86_64 -OutFile $env:TEMP\rustup-init.exe; & $env:TEMP\rustup-init.exe -y";
+
+/// Installers downloaded from the internet, which are only run after asking.
+pub const DOWNLOADED_INSTALLERS: &[&str] = &[RUSTUP_INSTALLER, RUSTUP_INIT_DOWNLOAD];
 
 pub trait Platform {
     fn name(&self) -> &'static str;
 
-    async fn check_dependencies(&self) -> anyhow::Result<Vec<Dependency>>;
+    async fn check_dependencies(&self) -> anyhow::Result<Report>;
 
     async fn install_dependencies(&self, dependencies: &[Dependency]) -> anyhow::Result<()>;
 
     fn install_instructions(&self, dependencies: &[Dependency]) -> String;
+
+    /// Rust targets needed on this platform besides the host's, which rustup always installs.
+    fn rust_targets(&self) -> &'static [Dependency] {
+        &[]
+    }
+
+    /// The commands that install rustup.
+    fn rustup_install(&self) -> Vec<Vec<&'static str>> {
+        vec![vec!["sh", "-c", RUSTUP_INSTALLER]]
+    }
+}
+
+/// Runs external programs for a platform backend, so the checks can be tested with canned output.
+pub trait CommandRunner {
+    /// The standard output of `program`, or `None` if it could not be started or failed.
+    fn output(&self, program: &str, args: &[&str]) -> Option<String>;
+
+    /// Runs `program` attached to the terminal and returns whether it succeeded.
+    fn run(&self, program: &str, args: &[&str]) -> anyhow::Result<bool>;
+}
+
+/// Runs the programs installed on this machine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn output(&self, program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program)
+            .args(args)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> anyhow::Result<bool> {
+        let status = Command::new(program)
+            .args(args)
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e))?;
+
+        Ok(status.success())
+    }
+}
+
+/// Formats a command for copying into a shell, quoting arguments with spaces.
+pub fn format_command(command: &[&str]) -> String {
+    let args: Vec<_> = command
+        .iter()
+        .map(|arg| {
+            if arg.contains(' ') {
+                format!("\"{}\"", arg)
+            } else {
+                arg.to_string()
+            }
+        })
+        .collect();
+
+    args.join(" ")
 }
 
 pub fn current() -> CurrentPlatform {
-    CurrentPlatform
+    CurrentPlatform::default()
 }
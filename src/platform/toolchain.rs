@@ -0,0 +1,475 @@
+use super::{CommandRunner, DOWNLOADED_INSTALLERS, Platform, SystemRunner, format_command};
+use crate::commands::dependencies::{Dependency, Installed, Report};
+use anyhow::Result;
+use console::Term;
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+use semver::Version;
+
+/// Generated projects use edition 2024, which was stabilized in Rust 1.85.
+const MIN_RUST_VERSION: Version = Version::new(1, 85, 0);
+
+const RUSTUP: Dependency = Dependency {
+    name: "rustup",
+    info: "installs and updates the Rust toolchain",
+};
+
+const DEFAULT_TOOLCHAIN: Dependency = Dependency {
+    name: "Rust toolchain",
+    info: "rustup has no default toolchain",
+};
+
+const RUST_VERSION: Dependency = Dependency {
+    name: "Rust 1.85 or newer",
+    info: "generated projects use edition 2024",
+};
+
+const CARGO: Dependency = Dependency {
+    name: "cargo",
+    info: "the Rust build tool and package manager",
+};
+
+const COMPONENTS: [Dependency; 2] = [
+    Dependency {
+        name: "clippy",
+        info: "the Rust linter",
+    },
+    Dependency {
+        name: "rustfmt",
+        info: "the Rust code formatter",
+    },
+];
+
+/// Checks the Rust toolchain through rustup, independent of the platform's system libraries.
+pub struct Toolchain<R: CommandRunner = SystemRunner> {
+    runner: R,
+    targets: &'static [Dependency],
+    rustup_install: Vec<Vec<&'static str>>,
+    /// Asks whether a downloaded installer script may run.
+    confirm: fn(&str) -> Result<bool>,
+}
+
+impl Toolchain {
+    pub fn new(platform: &impl Platform) -> Self {
+        Self {
+            runner: SystemRunner,
+            targets: platform.rust_targets(),
+            rustup_install: platform.rustup_install(),
+            confirm: confirm_script,
+        }
+    }
+}
+
+impl<R: CommandRunner> Toolchain<R> {
+    #[cfg(test)]
+    fn with_runner(
+        runner: R,
+        targets: &'static [Dependency],
+        rustup_install: Vec<Vec<&'static str>>,
+    ) -> Self {
+        Self {
+            runner,
+            targets,
+            rustup_install,
+            confirm: |_| Ok(false),
+        }
+    }
+
+    pub fn check(&self) -> Report {
+        let mut report = Report::default();
+
+        let rustup = self.version("rustup", &["--version"]);
+        match &rustup {
+            Some(version) => report.installed.push(Installed {
+                name: "rustup".to_string(),
+                version: version.clone(),
+            }),
+            None => report.missing.push(RUSTUP),
+        }
+
+        // a toolchain from a distribution package or Homebrew works without rustup, and is the
+        // one most likely to be too old
+        let rustc = self.version("rustc", &["--version"]);
+        match &rustc {
+            Some(version) => {
+                if !is_supported(version) {
+                    report.missing.push(RUST_VERSION);
+                }
+                report.installed.push(Installed {
+                    name: "rustc".to_string(),
+                    version: version.clone(),
+                });
+            }
+            // installing rustup installs a toolchain as well
+            None if rustup.is_some() => report.missing.push(DEFAULT_TOOLCHAIN),
+            None => {}
+        }
+
+        match self.version("cargo", &["--version"]) {
+            Some(version) => report.installed.push(Installed {
+                name: "cargo".to_string(),
+                version,
+            }),
+            None => report.missing.push(CARGO),
+        }
+
+        // components and targets are listed through rustup, for the active toolchain
+        if rustup.is_none() || rustc.is_none() {
+            report.skipped.extend(COMPONENTS);
+            report.skipped.extend(self.targets.iter().cloned());
+            return report;
+        }
+
+        // component names carry the host triple, e.g. `clippy-x86_64-unknown-linux-gnu`
+        let components = self.installed_lines(&["component", "list", "--installed"]);
+        for component in COMPONENTS {
+            let prefix = format!("{}-", component.name);
+            if components
+                .iter()
+                .any(|line| *line == component.name || line.starts_with(&prefix))
+            {
+                report.installed.push(Installed {
+                    name: component.name.to_string(),
+                    version: String::new(),
+                });
+            } else {
+                report.missing.push(component);
+            }
+        }
+
+        let targets = self.installed_lines(&["target", "list", "--installed"]);
+        for target in self.targets {
+            if targets.iter().any(|line| line == target.name) {
+                report.installed.push(Installed {
+                    name: target.name.to_string(),
+                    version: String::new(),
+                });
+            } else {
+                report.missing.push(target.clone());
+            }
+        }
+
+        report
+    }
+
+    pub fn install(&self, missing: &[Dependency]) -> Result<()> {
+        for command in self.fix_commands(missing) {
+            // never run an installer from the internet without asking
+            if let Some(installer) = DOWNLOADED_INSTALLERS
+                .iter()
+                .find(|installer| command.contains(installer))
+                && !(self.confirm)(installer)?
+            {
+                anyhow::bail!(
+                    "rustup was not installed. To install it yourself, run:\n  {}",
+                    installer
+                );
+            }
+
+            if !self.runner.run(command[0], &command[1..])? {
+                anyhow::bail!("Failed to run: {}", format_command(&command));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn install_instructions(&self, missing: &[Dependency]) -> String {
+        let commands: Vec<_> = self
+            .fix_commands(missing)
+            .iter()
+            .map(|command| format_command(command))
+            .collect();
+
+        commands.join("\n")
+    }
+
+    /// The rustup commands that fix `missing`, in the order they have to run.
+    fn fix_commands(&self, missing: &[Dependency]) -> Vec<Vec<&'static str>> {
+        let mut commands = Vec::new();
+
+        if missing.contains(&RUSTUP) {
+            commands.extend(self.rustup_install.iter().cloned());
+        }
+        if missing.contains(&DEFAULT_TOOLCHAIN) {
+            commands.push(vec!["rustup", "default", "stable"]);
+        }
+        // a fresh rustup installs the latest stable toolchain
+        if missing.contains(&RUST_VERSION) && !missing.contains(&RUSTUP) {
+            commands.push(vec!["rustup", "update"]);
+        }
+
+        // a new rustup or default toolchain comes with cargo
+        let cargo = missing.contains(&CARGO)
+            && !missing.contains(&RUSTUP)
+            && !missing.contains(&DEFAULT_TOOLCHAIN);
+        let components: Vec<_> = cargo
+            .then_some(&CARGO)
+            .into_iter()
+            .chain(&COMPONENTS)
+            .filter(|component| missing.contains(component))
+            .map(|component| component.name)
+            .collect();
+        if !components.is_empty() {
+            commands.push([&["rustup", "component", "add"][..], &components].concat());
+        }
+
+        let targets: Vec<_> = self
+            .targets
+            .iter()
+            .filter(|target| missing.contains(target))
+            .map(|target| target.name)
+            .collect();
+        if !targets.is_empty() {
+            commands.push([&["rustup", "target", "add"][..], &targets].concat());
+        }
+
+        commands
+    }
+
+    /// The version in the output of `<program> --version`, e.g. `1.90.0` for
+    /// `rustc 1.90.0 (1159e78c4 2025-09-14)`.
+    fn version(&self, program: &str, args: &[&str]) -> Option<String> {
+        let output = self.runner.output(program, args)?;
+
+        output.split_whitespace().nth(1).map(str::to_string)
+    }
+
+    fn installed_lines(&self, args: &[&str]) -> Vec<String> {
+        self.runner
+            .output("rustup", args)
+            .map(|output| output.lines().map(|line| line.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Shows the installer command and asks before running it. Without a terminal the answer is no.
+fn confirm_script(command: &str) -> Result<bool> {
+    if !Term::stdout().is_term() {
+        return Ok(false);
+    }
+
+    println!(
+        "rustup is installed with its official installer:\n  {}",
+        command
+    );
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Download and run it?")
+        .default(false)
+        .interact()?)
+}
+
+/// Whether a rustc version, e.g. `1.92.0-nightly`, is at least [`MIN_RUST_VERSION`]. Nightlies
+/// and betas count as the release they lead up to.
+fn is_supported(version: &str) -> bool {
+    match Version::parse(version) {
+        Ok(version) => {
+            (version.major, version.minor, version.patch)
+                >= (
+                    MIN_RUST_VERSION.major,
+                    MIN_RUST_VERSION.minor,
+                    MIN_RUST_VERSION.patch,
+                )
+        }
+        // an unknown version format shouldn't block anyone
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{FakeRunner, RUSTUP_INIT_DOWNLOAD, RUSTUP_INSTALLER};
+
+    const TARGETS: &[Dependency] = &[Dependency {
+        name: "x86_64-apple-darwin",
+        info: "Rust target for Intel Macs",
+    }];
+
+    fn complete_setup() -> FakeRunner {
+        FakeRunner::default()
+            .with("rustup --version", "rustup 1.28.2 (e4f3ad6f8 2025-04-28)\n")
+            .with("rustc --version", "rustc 1.90.0 (1159e78c4 2025-09-14)\n")
+            .with("cargo --version", "cargo 1.90.0 (840b83a10 2025-07-30)\n")
+            .with(
+                "rustup component list --installed",
+                "cargo-aarch64-apple-darwin\nclippy-aarch64-apple-darwin\n\
+                 rust-std-aarch64-apple-darwin\nrustc-aarch64-apple-darwin\n\
+                 rustfmt-aarch64-apple-darwin\n",
+            )
+            .with(
+                "rustup target list --installed",
+                "aarch64-apple-darwin\nx86_64-apple-darwin\n",
+            )
+    }
+
+    fn toolchain(runner: FakeRunner) -> Toolchain<FakeRunner> {
+        Toolchain::with_runner(runner, TARGETS, vec![vec!["brew", "install", "rustup"]])
+    }
+
+    fn names(dependencies: &[Dependency]) -> Vec<&'static str> {
+        dependencies.iter().map(|dep| dep.name).collect()
+    }
+
+    #[test]
+    fn complete_setup_has_nothing_missing() {
+        let report = toolchain(complete_setup()).check();
+
+        assert!(report.missing.is_empty());
+        assert!(report.skipped.is_empty());
+        let installed: Vec<_> = report
+            .installed
+            .iter()
+            .map(|dep| format!("{} {}", dep.name, dep.version))
+            .collect();
+        assert_eq!(
+            installed,
+            [
+                "rustup 1.28.2",
+                "rustc 1.90.0",
+                "cargo 1.90.0",
+                "clippy ",
+                "rustfmt ",
+                "x86_64-apple-darwin "
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_rustup_is_installed_the_platform_way() {
+        let toolchain = toolchain(FakeRunner::default());
+        let report = toolchain.check();
+
+        assert_eq!(names(&report.missing), ["rustup", "cargo"]);
+        assert_eq!(
+            names(&report.skipped),
+            ["clippy", "rustfmt", "x86_64-apple-darwin"]
+        );
+        assert_eq!(
+            toolchain.install_instructions(&report.missing),
+            "brew install rustup"
+        );
+    }
+
+    #[test]
+    fn missing_cargo_is_added_as_component() {
+        let mut runner = complete_setup();
+        runner.outputs.remove("cargo --version");
+        let toolchain = toolchain(runner);
+        let report = toolchain.check();
+
+        assert_eq!(names(&report.missing), ["cargo"]);
+        assert!(!report.installed.iter().any(|dep| dep.name == "cargo"));
+        assert_eq!(
+            toolchain.install_instructions(&report.missing),
+            "rustup component add cargo"
+        );
+    }
+
+    #[test]
+    fn downloaded_installers_need_confirmation() {
+        for rustup_install in [
+            vec!["sh", "-c", RUSTUP_INSTALLER],
+            vec!["powershell", "-NoProfile", "-Command", RUSTUP_INIT_DOWNLOAD],
+        ] {
+            let mut toolchain =
+                Toolchain::with_runner(FakeRunner::default(), TARGETS, vec![rustup_install]);
+            let missing = toolchain.check().missing;
+
+            assert!(toolchain.install(&missing).is_err());
+            assert!(toolchain.runner.ran.borrow().is_empty());
+
+            toolchain.confirm = |_| Ok(true);
+            toolchain.install(&missing).unwrap();
+            assert_eq!(toolchain.runner.ran.borrow().len(), 1);
+        }
+    }
+
+    #[test]
+    fn toolchain_without_rustup_is_still_checked() {
+        let mut runner = complete_setup()
+            .with("rustc --version", "rustc 1.75.0 (82e1608df 2023-12-21)\n")
+            .with("cargo --version", "cargo 1.75.0 (1d8b05cdd 2023-11-20)\n");
+        runner.outputs.remove("rustup --version");
+        let toolchain = toolchain(runner);
+        let report = toolchain.check();
+
+        assert_eq!(names(&report.missing), ["rustup", "Rust 1.85 or newer"]);
+        let installed: Vec<_> = report
+            .installed
+            .iter()
+            .map(|dep| format!("{} {}", dep.name, dep.version))
+            .collect();
+        assert_eq!(installed, ["rustc 1.75.0", "cargo 1.75.0"]);
+        assert_eq!(
+            names(&report.skipped),
+            ["clippy", "rustfmt", "x86_64-apple-darwin"]
+        );
+        // the new rustup brings a current toolchain
+        assert_eq!(
+            toolchain.install_instructions(&report.missing),
+            "brew install rustup"
+        );
+    }
+
+    #[test]
+    fn old_toolchain_is_updated() {
+        let runner =
+            complete_setup().with("rustc --version", "rustc 1.80.1 (3f5fd8dd4 2024-08-06)\n");
+        let toolchain = toolchain(runner);
+        let report = toolchain.check();
+
+        assert_eq!(names(&report.missing), ["Rust 1.85 or newer"]);
+        assert_eq!(
+            toolchain.install_instructions(&report.missing),
+            "rustup update"
+        );
+    }
+
+    #[test]
+    fn nightly_counts_as_its_release() {
+        assert!(is_supported("1.85.0-nightly"));
+        assert!(is_supported("1.92.0-beta.3"));
+        assert!(!is_supported("1.84.1"));
+    }
+
+    #[test]
+    fn missing_default_toolchain_is_set() {
+        let mut runner = complete_setup();
+        runner.outputs.remove("rustc --version");
+        let toolchain = toolchain(runner);
+        let report = toolchain.check();
+
+        assert_eq!(names(&report.missing), ["Rust toolchain"]);
+        assert_eq!(
+            toolchain.install_instructions(&report.missing),
+            "rustup default stable"
+        );
+    }
+
+    #[test]
+    fn missing_components_and_targets_are_added() {
+        let runner = complete_setup()
+            .with(
+                "rustup component list --installed",
+                "cargo-x86_64-unknown-linux-gnu\nrustc-x86_64-unknown-linux-gnu\n",
+            )
+            .with("rustup target list --installed", "aarch64-apple-darwin\n");
+        let toolchain = toolchain(runner);
+        let report = toolchain.check();
+
+        assert_eq!(
+            names(&report.missing),
+            ["clippy", "rustfmt", "x86_64-apple-darwin"]
+        );
+
+        toolchain.install(&report.missing).unwrap();
+        assert_eq!(
+            *toolchain.runner.ran.borrow(),
+            [
+                "rustup component add clippy rustfmt",
+                "rustup target add x86_64-apple-darwin"
+            ]
+        );
+    }
+}
@@ -1,26 +1,374 @@
-use super::Platform;
-use crate::commands::dependencies::Dependency;
+use super::{CommandRunner, Platform, RUSTUP_INIT_DOWNLOAD, SystemRunner, format_command};
+use crate::commands::dependencies::{Dependency, Report};
 use anyhow::Result;
-use std::process::Command;
 
-pub struct Windows;
+const MSVC: Dependency = Dependency {
+    name: "MSVC build tools",
+    info: "the Visual Studio C++ compiler and linker",
+};
 
-impl Platform for Windows {
+const WINDOWS_SDK: Dependency = Dependency {
+    name: "Windows SDK",
+    info: "Windows headers and libraries",
+};
+
+const TARGETS: &[Dependency] = &[Dependency {
+    name: "x86_64-pc-windows-msvc",
+    info: "Rust target for 64-bit Windows",
+}];
+
+const GIT: Dependency = Dependency {
+    name: "Git",
+    info: "version control, also used by cargo to fetch nih-plug",
+};
+
+/// The Visual Studio component that contains the x64 C++ compiler and linker.
+const VC_TOOLS_COMPONENT: &str = "Microsoft.VisualStudio.Component.VC.Tools.x86.x64";
+
+/// Where the Windows 10 and 11 SDKs register themselves.
+const SDK_REGISTRY_KEY: &str = r"HKLM\SOFTWARE\WOW6432Node\Microsoft\Microsoft SDKs\Windows\v10.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Winget,
+    Chocolatey,
+}
+
+#[derive(Default)]
+pub struct Windows<R: CommandRunner = SystemRunner> {
+    runner: R,
+}
+
+impl<R: CommandRunner> Windows<R> {
+    #[cfg(test)]
+    fn with_runner(runner: R) -> Self {
+        Self { runner }
+    }
+
+    fn has_msvc(&self) -> bool {
+        self.runner
+            .output(
+                &vswhere_path(),
+                &[
+                    "-latest",
+                    "-products",
+                    "*",
+                    "-requires",
+                    VC_TOOLS_COMPONENT,
+                    "-format",
+                    "value",
+                    "-property",
+                    "installationPath",
+                ],
+            )
+            .is_some_and(|output| !output.trim().is_empty())
+    }
+
+    fn has_windows_sdk(&self) -> bool {
+        self.runner
+            .output(
+                "reg",
+                &["query", SDK_REGISTRY_KEY, "/v", "InstallationFolder"],
+            )
+            .is_some_and(|output| output.contains("REG_SZ"))
+    }
+
+    fn has_git(&self) -> bool {
+        self.runner.output("git", &["--version"]).is_some()
+    }
+
+    /// winget when it is available, Chocolatey otherwise.
+    fn package_manager(&self) -> Option<PackageManager> {
+        if self.runner.output("winget", &["--version"]).is_some() {
+            Some(PackageManager::Winget)
+        } else if self.runner.output("choco", &["--version"]).is_some() {
+            Some(PackageManager::Chocolatey)
+        } else {
+            None
+        }
+    }
+
+    /// The commands that install `dependencies`, in the order they have to run.
+    fn install_commands(
+        &self,
+        dependencies: &[Dependency],
+        manager: PackageManager,
+    ) -> Vec<Vec<&'static str>> {
+        let mut commands = Vec::new();
+
+        // the build tools workload includes the recommended Windows SDK
+        if dependencies.contains(&MSVC) {
+            commands.push(match manager {
+                PackageManager::Winget => vec![
+                    "winget",
+                    "install",
+                    "--id",
+                    "Microsoft.VisualStudio.2022.BuildTools",
+                    "--override",
+                    "--wait --passive --add Microsoft.VisualStudio.Workload.VCTools --includeRecommended",
+                ],
+                PackageManager::Chocolatey => vec![
+                    "choco",
+                    "install",
+                    "-y",
+                    "visualstudio2022buildtools",
+                    "visualstudio2022-workload-vctools",
+                ],
+            });
+        } else if dependencies.contains(&WINDOWS_SDK) {
+            commands.push(match manager {
+                PackageManager::Winget => vec![
+                    "winget",
+                    "install",
+                    "--id",
+                    "Microsoft.WindowsSDK.10.0.22621",
+                ],
+                PackageManager::Chocolatey => vec!["choco", "install", "-y", "windows-sdk-10.1"],
+            });
+        }
+
+        if dependencies.contains(&GIT) {
+            commands.push(match manager {
+                PackageManager::Winget => vec!["winget", "install", "--id", "Git.Git"],
+                PackageManager::Chocolatey => vec!["choco", "install", "-y", "git"],
+            });
+        }
+
+        commands
+    }
+}
+
+impl<R: CommandRunner> Platform for Windows<R> {
     fn name(&self) -> &'static str {
         "Windows"
     }
 
-    async fn check_dependencies(&self) -> anyhow::Result<Vec<Dependency>> {
-        let missing = Vec::<Dependency>::new();
+    async fn check_dependencies(&self) -> Result<Report> {
+        let mut missing = Vec::new();
+
+        if !self.has_msvc() {
+            missing.push(MSVC);
+        }
+        if !self.has_windows_sdk() {
+            missing.push(WINDOWS_SDK);
+        }
+        if !self.has_git() {
+            missing.push(GIT);
+        }
 
-        Ok(missing)
+        Ok(Report {
+            missing,
+            ..Default::default()
+        })
     }
 
-    async fn install_dependencies(&self, dependencies: &[Dependency]) -> anyhow::Result<()> {
+    async fn install_dependencies(&self, dependencies: &[Dependency]) -> Result<()> {
+        let Some(manager) = self.package_manager() else {
+            anyhow::bail!(
+                "Neither winget nor Chocolatey is installed. Install winget from the Microsoft Store (App Installer) and run this again."
+            );
+        };
+
+        for command in self.install_commands(dependencies, manager) {
+            if !self.runner.run(command[0], &command[1..])? {
+                anyhow::bail!("Failed to run: {}", format_command(&command));
+            }
+        }
+
         Ok(())
     }
 
     fn install_instructions(&self, dependencies: &[Dependency]) -> String {
-        "".to_string()
+        let Some(manager) = self.package_manager() else {
+            let downloads: Vec<_> = dependencies
+                .iter()
+                .map(|dep| format!("  {}: {}", dep.name, download_url(dep)))
+                .collect();
+
+            return format!(
+                "Neither winget nor Chocolatey is installed. Install winget from the Microsoft Store (App Installer), or download the installers:\n{}",
+                downloads.join("\n")
+            );
+        };
+        let commands: Vec<_> = self
+            .install_commands(dependencies, manager)
+            .iter()
+            .map(|command| format_command(command))
+            .collect();
+
+        commands.join("\n")
+    }
+
+    fn rust_targets(&self) -> &'static [Dependency] {
+        TARGETS
+    }
+
+    fn rustup_install(&self) -> Vec<Vec<&'static str>> {
+        match self.package_manager() {
+            Some(PackageManager::Chocolatey) => {
+                vec![vec!["choco", "install", "-y", "rustup.install"]]
+            }
+            Some(PackageManager::Winget) => {
+                vec![vec!["winget", "install", "--id", "Rustlang.Rustup"]]
+            }
+            None => vec![vec![
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                RUSTUP_INIT_DOWNLOAD,
+            ]],
+        }
+    }
+}
+
+/// Where to download a dependency's installer by hand.
+fn download_url(dependency: &Dependency) -> &'static str {
+    if *dependency == MSVC {
+        "https://visualstudio.microsoft.com/visual-cpp-build-tools/"
+    } else if *dependency == WINDOWS_SDK {
+        "https://developer.microsoft.com/windows/downloads/windows-sdk/"
+    } else {
+        "https://git-scm.com/downloads/win"
+    }
+}
+
+fn vswhere_path() -> String {
+    let program_files = std::env::var("ProgramFiles(x86)")
+        .unwrap_or_else(|_| r"C:\Program Files (x86)".to_string());
+
+    format!(
+        r"{}\Microsoft Visual Studio\Installer\vswhere.exe",
+        program_files
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::FakeRunner;
+
+    const VSWHERE_ARGS: &str = "-latest -products * -requires Microsoft.VisualStudio.Component.VC.Tools.x86.x64 -format value -property installationPath";
+
+    const REG_QUERY: &str = r"reg query HKLM\SOFTWARE\WOW6432Node\Microsoft\Microsoft SDKs\Windows\v10.0 /v InstallationFolder";
+
+    const REG_OUTPUT: &str = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\WOW6432Node\\Microsoft\\Microsoft SDKs\\Windows\\v10.0\r\n    InstallationFolder    REG_SZ    C:\\Program Files (x86)\\Windows Kits\\10\\\r\n\r\n";
+
+    fn vswhere() -> String {
+        format!("{} {}", vswhere_path(), VSWHERE_ARGS)
+    }
+
+    fn complete_setup() -> FakeRunner {
+        FakeRunner::default()
+            .with(
+                &vswhere(),
+                "C:\\Program Files (x86)\\Microsoft Visual Studio\\2022\\BuildTools\r\n",
+            )
+            .with(REG_QUERY, REG_OUTPUT)
+            .with("git --version", "git version 2.51.0.windows.1\n")
+    }
+
+    fn names(dependencies: &[Dependency]) -> Vec<&'static str> {
+        dependencies.iter().map(|dep| dep.name).collect()
+    }
+
+    #[tokio::test]
+    async fn complete_setup_has_nothing_missing() {
+        let windows = Windows::with_runner(complete_setup());
+
+        assert!(
+            windows
+                .check_dependencies()
+                .await
+                .unwrap()
+                .missing
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn visual_studio_without_cpp_tools_is_missing_msvc() {
+        // vswhere succeeds without output when no installation has the required component
+        let windows = Windows::with_runner(complete_setup().with(&vswhere(), ""));
+        let missing = windows.check_dependencies().await.unwrap().missing;
+
+        assert_eq!(names(&missing), ["MSVC build tools"]);
+    }
+
+    #[tokio::test]
+    async fn fresh_machine_gets_winget_instructions() {
+        let runner = FakeRunner::default().with("winget --version", "v1.11.430\n");
+        let windows = Windows::with_runner(runner);
+        let missing = windows.check_dependencies().await.unwrap().missing;
+
+        assert_eq!(names(&missing), ["MSVC build tools", "Windows SDK", "Git"]);
+        assert_eq!(
+            windows.install_instructions(&missing),
+            "winget install --id Microsoft.VisualStudio.2022.BuildTools --override \"--wait --passive --add Microsoft.VisualStudio.Workload.VCTools --includeRecommended\"\n\
+             winget install --id Git.Git"
+        );
+    }
+
+    #[tokio::test]
+    async fn chocolatey_is_used_without_winget() {
+        let mut runner = complete_setup().with("choco --version", "2.5.1\n");
+        runner.outputs.remove(REG_QUERY);
+        runner.outputs.remove("git --version");
+        let windows = Windows::with_runner(runner);
+        let missing = windows.check_dependencies().await.unwrap().missing;
+        windows.install_dependencies(&missing).await.unwrap();
+
+        assert_eq!(
+            *windows.runner.ran.borrow(),
+            ["choco install -y windows-sdk-10.1", "choco install -y git"]
+        );
+    }
+
+    #[tokio::test]
+    async fn install_without_package_manager_fails() {
+        let windows = Windows::with_runner(FakeRunner::default());
+        let missing = windows.check_dependencies().await.unwrap().missing;
+
+        assert!(windows.install_dependencies(&missing).await.is_err());
+        assert!(windows.runner.ran.borrow().is_empty());
+    }
+
+    #[tokio::test]
+    async fn instructions_without_package_manager_link_the_installers() {
+        let windows = Windows::with_runner(FakeRunner::default());
+        let missing = windows.check_dependencies().await.unwrap().missing;
+        let instructions = windows.install_instructions(&missing);
+
+        assert!(!instructions.contains("winget install"));
+        assert!(instructions.contains(
+            "MSVC build tools: https://visualstudio.microsoft.com/visual-cpp-build-tools/"
+        ));
+        assert!(instructions.contains("Git: https://git-scm.com/downloads/win"));
+    }
+
+    #[test]
+    fn rustup_comes_from_the_package_manager() {
+        let runner = FakeRunner::default().with("choco --version", "2.5.1\n");
+        assert_eq!(
+            Windows::with_runner(runner).rustup_install(),
+            [vec!["choco", "install", "-y", "rustup.install"]]
+        );
+
+        let runner = FakeRunner::default().with("winget --version", "v1.11.430\n");
+        assert_eq!(
+            Windows::with_runner(runner).rustup_install(),
+            [vec!["winget", "install", "--id", "Rustlang.Rustup"]]
+        );
+
+        // without either, rustup-init.exe is downloaded
+        let windows = Windows::with_runner(FakeRunner::default());
+        assert_eq!(
+            windows.rustup_install(),
+            [vec![
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                RUSTUP_INIT_DOWNLOAD
+            ]]
+        );
     }
 }
@@ -0,0 +1,178 @@
+use crate::templates::names;
+use anyhow::{Context, Result};
+use minijinja::{Environment, Error, ErrorKind, UndefinedBehavior};
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The templates compiled into the binary, by name.
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "project/cargo_project.txt",
+        include_str!("../../templates/project/cargo_project.txt"),
+    ),
+    (
+        "project/main.txt",
+        include_str!("../../templates/project/main.txt"),
+    ),
+    (
+        "project/lib_effect.txt",
+        include_str!("../../templates/project/lib_effect.txt"),
+    ),
+    (
+        "project/lib_instrument.txt",
+        include_str!("../../templates/project/lib_instrument.txt"),
+    ),
+    (
+        "project/lib_midi.txt",
+        include_str!("../../templates/project/lib_midi.txt"),
+    ),
+    (
+        "project/lib_analyzer.txt",
+        include_str!("../../templates/project/lib_analyzer.txt"),
+    ),
+    (
+        "project/formats.txt",
+        include_str!("../../templates/project/formats.txt"),
+    ),
+    (
+        "project/editor_iced.txt",
+        include_str!("../../templates/project/editor_iced.txt"),
+    ),
+    (
+        "project/editor_egui.txt",
+        include_str!("../../templates/project/editor_egui.txt"),
+    ),
+    (
+        "project/editor_vizia.txt",
+        include_str!("../../templates/project/editor_vizia.txt"),
+    ),
+    (
+        "xtask/main.rs",
+        include_str!("../../templates/xtask/main.rs"),
+    ),
+    (
+        "xtask/cargo_xtask.txt",
+        include_str!("../../templates/xtask/cargo_xtask.txt"),
+    ),
+    (
+        "xtask/cargo_workspace.txt",
+        include_str!("../../templates/xtask/cargo_workspace.txt"),
+    ),
+    (
+        "xtask/bundler.toml",
+        include_str!("../../templates/xtask/bundler.toml"),
+    ),
+    (
+        "xtask/cargo_config.toml",
+        include_str!("../../templates/xtask/cargo_config.toml"),
+    ),
+    (
+        "git/gitignore.txt",
+        include_str!("../../templates/git/gitignore.txt"),
+    ),
+    (
+        "git/ci_cd_general.yaml",
+        include_str!("../../templates/git/ci_cd_general.yaml"),
+    ),
+];
+
+/// Creates a template environment with the built-in templates and filters. Using an undefined
+/// variable is an error instead of silently rendering nothing. Templates in `pack_dir` take
+/// precedence over built-in templates with the same name.
+pub fn environment(pack_dir: Option<PathBuf>) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+
+    env.add_filter("snake_case", |value: &str| names::snake_case(value));
+    env.add_filter("kebab_case", |value: &str| names::kebab_case(value));
+    env.add_filter("camel_case", |value: &str| names::camel_case(value));
+    env.add_filter("title_case", |value: &str| names::title_case(value));
+    env.add_filter("rust_str", rust_str);
+    env.add_filter("toml_str", toml_str);
+
+    env.set_loader(move |name| {
+        if let Some(pack_dir) = &pack_dir {
+            let path = pack_dir.join(name);
+
+            if is_relative_path(name) && path.is_file() {
+                return fs::read_to_string(&path).map(Some).map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidOperation,
+                        format!("Failed to read template {}: {}", path.display(), e),
+                    )
+                });
+            }
+        }
+
+        Ok(builtin_template(name).map(str::to_string))
+    });
+
+    env
+}
+
+pub fn builtin_template(name: &str) -> Option<&'static str> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, source)| *source)
+}
+
+/// Whether `path` stays inside the directory it is joined to.
+pub fn is_relative_path(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Renders a single built-in template outside of a project, e.g. for `nih-bootstrap git`.
+pub fn render_builtin(name: &str, context: impl Serialize) -> Result<String> {
+    render(&environment(None), name, context)
+}
+
+pub fn render(env: &Environment, name: &str, context: impl Serialize) -> Result<String> {
+    env.get_template(name)
+        .and_then(|template| template.render(context))
+        .with_context(|| format!("Failed to render template: {}", name))
+}
+
+/// Escapes a value for use inside a Rust string literal.
+fn rust_str(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.extend(c.escape_unicode()),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes a value for use inside a TOML basic string.
+fn toml_str(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
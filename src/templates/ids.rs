@@ -0,0 +1,176 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+
+/// Derives a VST3 class ID from the vendor and project name. The same inputs always give the
+/// same ID, so regenerating a project keeps existing host sessions working.
+pub fn vst3_class_id(vendor: &str, project_name: &str) -> [u8; 16] {
+    let digest = Sha256::new()
+        .chain_update("nih-bootstrap vst3 class id\0")
+        .chain_update(vendor.trim())
+        .chain_update("\0")
+        .chain_update(project_name)
+        .finalize();
+
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&digest[..16]);
+    id
+}
+
+/// A VST3 class ID from the OS random number generator.
+pub fn random_vst3_class_id() -> Result<[u8; 16]> {
+    let mut id = [0u8; 16];
+    getrandom::fill(&mut id)
+        .map_err(|e| anyhow::anyhow!("Failed to generate a random class ID: {}", e))?;
+
+    Ok(id)
+}
+
+/// Formats a class ID as a Rust `[u8; 16]` literal.
+pub fn format_class_id(id: &[u8; 16]) -> String {
+    let bytes: Vec<_> = id.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+
+    format!("[{}]", bytes.join(", "))
+}
+
+/// Builds a reverse domain name CLAP ID such as `com.vendor.project-name`. The domain is taken
+/// from the vendor URL when there is one, otherwise from the vendor name.
+pub fn clap_id(vendor_url: &str, vendor: &str, project_name: &str) -> String {
+    let host = vendor_url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split(['/', ':', '?', '#']).next())
+        .map(|host| host.trim_start_matches("www."))
+        .filter(|host| !host.is_empty());
+
+    let domain = match host {
+        Some(host) => host
+            .split('.')
+            .rev()
+            .map(slug)
+            .collect::<Vec<_>>()
+            .join("."),
+        None if !slug(vendor).is_empty() => format!("com.{}", slug(vendor)),
+        None => "com.example".to_string(),
+    };
+
+    format!("{}.{}", domain, slug(project_name))
+}
+
+fn slug(s: &str) -> String {
+    let mut slug = String::new();
+
+    for c in s.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::names::ProjectName;
+
+    fn id_for(vendor: &str, name: &str) -> [u8; 16] {
+        vst3_class_id(vendor, &ProjectName::parse(name).unwrap().crate_name)
+    }
+
+    /// Whether `id` is a reverse domain name made of lowercase ASCII labels.
+    fn is_reverse_domain(id: &str) -> bool {
+        let labels: Vec<_> = id.split('.').collect();
+
+        labels.len() >= 3
+            && labels.iter().all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            })
+    }
+
+    #[test]
+    fn class_id_is_deterministic() {
+        assert_eq!(
+            vst3_class_id("Acme Audio", "my-cool-plugin"),
+            vst3_class_id("Acme Audio", "my-cool-plugin")
+        );
+        // every spelling of the same name is the same project
+        assert_eq!(
+            id_for("Acme Audio", "My Cool Plugin"),
+            id_for("Acme Audio", "my_cool_plugin")
+        );
+    }
+
+    #[test]
+    fn class_id_changes_with_vendor_and_name() {
+        let id = id_for("Acme Audio", "my-plugin");
+
+        assert_ne!(id, id_for("Acme Audio Labs", "my-plugin"));
+        assert_ne!(id, id_for("Acme Audio", "my-plugin-2"));
+        // these only differ in case or separators, but are different crates
+        assert_ne!(id, id_for("Acme Audio", "myplugin"));
+        assert_ne!(
+            id_for("Acme Audio", "MyPlugin"),
+            id_for("Acme Audio", "Myplugin")
+        );
+        // the separator between vendor and name keeps them from running together
+        assert_ne!(vst3_class_id("ab", "c"), vst3_class_id("a", "bc"));
+    }
+
+    #[test]
+    fn random_class_ids_differ() {
+        let first = random_vst3_class_id().unwrap();
+        let second = random_vst3_class_id().unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn formats_class_id_as_byte_array() {
+        let mut id = [0u8; 16];
+        id[0] = 0xab;
+        id[15] = 0x01;
+
+        assert_eq!(
+            format_class_id(&id),
+            "[0xab, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]"
+        );
+    }
+
+    #[test]
+    fn clap_id_is_a_valid_reverse_domain() {
+        let cases = [
+            ("", "Acme Audio", "com.acme-audio.my-plugin"),
+            ("https://www.acme.audio", "Acme", "audio.acme.my-plugin"),
+            (
+                "https://acme.audio:8443/plugins/",
+                "Acme",
+                "audio.acme.my-plugin",
+            ),
+            ("", "Ünïcode Audio", "com.n-code-audio.my-plugin"),
+            ("", "音楽工房", "com.example.my-plugin"),
+            ("", "", "com.example.my-plugin"),
+            ("not a url", "Acme", "com.acme.my-plugin"),
+        ];
+
+        for (vendor_url, vendor, expected) in cases {
+            let id = clap_id(vendor_url, vendor, "my-plugin");
+
+            assert_eq!(id, expected, "{:?} {:?}", vendor_url, vendor);
+            assert!(is_reverse_domain(&id), "{}", id);
+        }
+    }
+
+    #[test]
+    fn slug_keeps_lowercase_ascii_words() {
+        assert_eq!(slug("  Acme   Audio!  "), "acme-audio");
+        assert_eq!(slug("Café Sound"), "caf-sound");
+        assert_eq!(slug("---"), "");
+    }
+}
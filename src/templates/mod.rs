@@ -1,129 +1,312 @@
 use crate::config::Config;
 use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use clap::ValueEnum;
+use minijinja::{Environment, Value, context};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
+pub mod engine;
+pub mod ids;
+pub mod names;
+pub mod pack;
+pub mod plan;
+
+pub use pack::TemplatePack;
+pub use plan::FilePlan;
+
+/// The plugin formats a generated project can be built as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginFormat {
+    Vst3,
+    Clap,
+    Standalone,
+}
+
+impl PluginFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PluginFormat::Vst3 => "VST3",
+            PluginFormat::Clap => "CLAP",
+            PluginFormat::Standalone => "Standalone",
+        }
+    }
+}
+
+/// The GUI frameworks a generated project's editor can be built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuiFramework {
+    Iced,
+    Egui,
+    Vizia,
+    None,
+}
+
+impl GuiFramework {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GuiFramework::Iced => "iced",
+            GuiFramework::Egui => "egui",
+            GuiFramework::Vizia => "VIZIA",
+            GuiFramework::None => "none (headless)",
+        }
+    }
+
+    pub fn has_editor(&self) -> bool {
+        *self != GuiFramework::None
+    }
+
+    /// The crate and type of the persisted editor state, for frameworks with an editor.
+    fn editor_state(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            GuiFramework::Iced => Some(("nih_plug_iced", "IcedState")),
+            GuiFramework::Egui => Some(("nih_plug_egui", "EguiState")),
+            GuiFramework::Vizia => Some(("nih_plug_vizia", "ViziaState")),
+            GuiFramework::None => None,
+        }
+    }
+}
+
+/// The kinds of plugin a project can start out as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    /// An audio effect, starting as a gain plugin
+    Effect,
+    /// A polyphonic sine synthesizer driven by note events
+    Instrument,
+    /// A note effect that transposes incoming notes
+    Midi,
+    /// A peak meter that passes audio through unchanged
+    Analyzer,
+}
+
+impl PluginKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PluginKind::Effect => "effect",
+            PluginKind::Instrument => "instrument",
+            PluginKind::Midi => "MIDI effect",
+            PluginKind::Analyzer => "analyzer",
+        }
+    }
+
+    /// The parameters the editor shows a control for, as `(field, name)` pairs.
+    fn params(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            PluginKind::Effect | PluginKind::Instrument => &[("gain", "Gain")],
+            PluginKind::Midi => &[("transpose", "Transpose")],
+            PluginKind::Analyzer => &[("decay", "Peak Decay")],
+        }
+    }
+
+    fn vst3_subcategories(&self) -> &'static [&'static str] {
+        match self {
+            PluginKind::Effect | PluginKind::Midi => &["Fx", "Tools"],
+            PluginKind::Instrument => &["Instrument", "Synth", "Stereo"],
+            PluginKind::Analyzer => &["Fx", "Analyzer"],
+        }
+    }
+
+    fn clap_features(&self) -> &'static [&'static str] {
+        match self {
+            PluginKind::Effect => &["AudioEffect", "Stereo"],
+            PluginKind::Instrument => &["Instrument", "Synthesizer", "Stereo"],
+            PluginKind::Midi => &["NoteEffect", "Utility"],
+            PluginKind::Analyzer => &["AudioEffect", "Analyzer", "Stereo"],
+        }
+    }
+}
+
+#[derive(Serialize)]
 pub struct TemplateContext<'a> {
     pub project_name: &'a str,
     pub underscored_name: &'a str,
     pub camelcase_name: &'a str,
+    pub display_name: &'a str,
     pub description: &'a str,
+    pub vst3_class_id: &'a str,
+    pub clap_id: &'a str,
     pub config: &'a Config,
 }
 
+/// The variables every template can use, with a short description.
+pub const VARIABLES: &[(&str, &str)] = &[
+    (
+        "project_name",
+        "the crate name in kebab-case, e.g. my-plugin",
+    ),
+    (
+        "underscored_name",
+        "the project name in snake_case, also the crate directory",
+    ),
+    ("camelcase_name", "the plugin struct name, e.g. MyPlugin"),
+    ("display_name", "the name hosts show, e.g. My Plugin"),
+    ("description", "the project description"),
+    ("vst3_class_id", "the VST3 class ID as a [u8; 16] literal"),
+    ("clap_id", "the reverse domain name CLAP ID"),
+    (
+        "config",
+        "the configuration, e.g. config.vendor or config.nih_plug_git",
+    ),
+    ("gui", "the GUI framework: iced, egui, vizia or none"),
+    (
+        "editor",
+        "the editor's gui_crate and state_type, none for headless projects",
+    ),
+    (
+        "kind",
+        "the plugin kind: effect, instrument, midi or analyzer",
+    ),
+    ("formats", "the selected formats: vst3, clap and standalone"),
+    (
+        "params",
+        "the parameters the editor shows, each with a field and a name",
+    ),
+    (
+        "vst3_subcategories",
+        "the VST3 subcategories of the plugin kind",
+    ),
+    ("clap_features", "the CLAP features of the plugin kind"),
+];
+
 pub struct TemplateRenderer {
-    gui_framework: String,
+    env: Environment<'static>,
+    pack: TemplatePack,
+    gui_framework: GuiFramework,
+    kind: PluginKind,
+    formats: Vec<PluginFormat>,
 }
 
 impl TemplateRenderer {
-    pub fn new(gui_framework: &str) -> Self {
+    pub fn new(
+        pack: TemplatePack,
+        gui_framework: GuiFramework,
+        kind: PluginKind,
+        formats: &[PluginFormat],
+    ) -> Self {
         Self {
-            gui_framework: gui_framework.to_string(),
+            env: engine::environment(pack.dir.clone()),
+            pack,
+            gui_framework,
+            kind,
+            formats: formats.to_vec(),
         }
     }
 
-    pub fn render_project(&self, target_path: &Path, context: &TemplateContext) -> Result<()> {
-        let project_dir = target_path.join(context.underscored_name);
-        fs::create_dir_all(&project_dir.join("src"))?;
-
-        // create 3 main project files: main.rs, lib.rs and editor.rs
-        self.render_file(
-            &project_dir.join("src/main.rs"),
-            include_str!("../../templates/project/main.txt"),
-            context,
-        )?;
-
-        self.render_file(
-            &project_dir.join("src/lib.rs"),
-            self.get_lib_template(),
-            context,
-        )?;
-
-        self.render_file(
-            &project_dir.join("src/editor.rs"),
-            self.get_editor_template(),
-            context,
-        )?;
-
-        self.render_file(
-            &project_dir.join("Cargo.toml"),
-            include_str!("../../templates/project/cargo_project.txt"),
-            context,
-        )?;
-
-        self.create_xtask_project(target_path, context)?;
-
-        Ok(())
-    }
+    /// Renders every file of the project into memory. Nothing is written until the plan is
+    /// committed with [`FilePlan::write`].
+    pub fn plan(&self, context: &TemplateContext) -> Result<FilePlan> {
+        self.pack.check_choice(self.gui_framework, self.kind)?;
+        let context = self.template_context(context)?;
+        let mut plan = FilePlan::default();
+
+        for file in self.pack.files() {
+            if let Some(when) = &file.when {
+                let enabled = self
+                    .env
+                    .compile_expression(when)
+                    .and_then(|expression| expression.eval(&context))
+                    .with_context(|| format!("Failed to evaluate condition: {}", when))?;
+
+                if !enabled.is_true() {
+                    continue;
+                }
+            }
 
-    fn get_lib_template(&self) -> &'static str {
-        match self.gui_framework.as_str() {
-            "iced" => include_str!("../../templates/project/lib_iced.txt"),
-            _ => include_str!("../../templates/project/lib_iced.txt"),
+            let path = self.render_str(&file.path, &context)?;
+            if !engine::is_relative_path(&path) {
+                anyhow::bail!(
+                    "Template pack '{}' writes outside the project directory: {}",
+                    self.pack.name,
+                    path
+                );
+            }
+
+            let template = self.render_str(&file.template, &context)?;
+            let content = engine::render(&self.env, &template, &context)?;
+            plan.add(PathBuf::from(path), content);
         }
+
+        Ok(plan)
     }
 
-    fn get_editor_template(&self) -> &'static str {
-        match self.gui_framework.as_str() {
-            "iced" => include_str!("../../templates/project/editor.txt"),
-            _ => include_str!("../../templates/project/editor.txt"),
+    /// Everything the templates can refer to: the project values, the selected GUI, kind and
+    /// formats, and the pack's own variables.
+    fn template_context(&self, project: &TemplateContext) -> Result<Value> {
+        let editor = self
+            .gui_framework
+            .editor_state()
+            .map(|(gui_crate, state_type)| context! { gui_crate, state_type });
+        let params: Vec<_> = self
+            .kind
+            .params()
+            .iter()
+            .map(|(field, name)| context! { field, name })
+            .collect();
+        let vst3_subcategories: Vec<_> = self
+            .kind
+            .vst3_subcategories()
+            .iter()
+            .map(|category| format!("Vst3SubCategory::{}", category))
+            .collect();
+        let clap_features: Vec<_> = self
+            .kind
+            .clap_features()
+            .iter()
+            .map(|feature| format!("ClapFeature::{}", feature))
+            .collect();
+
+        let builtin = context! {
+            gui => self.gui_framework,
+            editor,
+            kind => self.kind,
+            formats => self.formats,
+            params,
+            vst3_subcategories,
+            clap_features,
+            ..Value::from_serialize(project)
+        };
+
+        for required in &self.pack.manifest.requires {
+            let value = self
+                .env
+                .compile_expression(required)
+                .and_then(|expression| expression.eval(&builtin))
+                .with_context(|| format!("Invalid required variable: {}", required))?;
+
+            if value.is_undefined() {
+                anyhow::bail!(
+                    "Template pack '{}' requires the variable '{}', which nih-bootstrap does not provide.",
+                    self.pack.name,
+                    required
+                );
+            }
+        }
+
+        for name in self.pack.manifest.variables.keys() {
+            if builtin
+                .get_attr(name)
+                .is_ok_and(|value| !value.is_undefined())
+            {
+                anyhow::bail!(
+                    "Template pack '{}' defines the variable '{}', which is already a built-in variable.",
+                    self.pack.name,
+                    name
+                );
+            }
         }
-    }
 
-    fn render_file(&self, path: &Path, template: &str, context: &TemplateContext) -> Result<()> {
-        let content = template
-            .replace("%%PROJECT_NAME%%", context.project_name)
-            .replace("%%PROJECT_NAME_UNDERSCORED%%", context.underscored_name)
-            .replace("%%PROJECT_NAME_CAMELCASE%%", context.camelcase_name)
-            .replace("%%PROJECT_DESCRIPTION%%", context.description)
-            .replace("%%AUTHORS%%", &context.config.authors)
-            .replace(
-                "%%CARGO_PACKAGE_VERSION%%",
-                &context.config.cargo_pkg_version,
-            )
-            .replace("%%VENDOR%%", &context.config.vendor)
-            .replace("%%URL%%", &context.config.vendor_url)
-            .replace("%%EMAIL%%", &context.config.vendor_email)
-            .replace("%%NIH_PLUG_GIT%%", &context.config.nih_plug_git);
-
-        fs::write(path, content)
-            .with_context(|| format!("Failed to write file: {}", path.display()))?;
-
-        Ok(())
+        Ok(context! {
+            ..builtin,
+            ..Value::from_serialize(&self.pack.manifest.variables)
+        })
     }
 
-    fn create_xtask_project(&self, target_path: &Path, context: &TemplateContext) -> Result<()> {
-        let xtask_dir = target_path.join("xtask");
-        fs::create_dir_all(&xtask_dir.join("src"))?;
-
-        // xtask files
-        fs::write(
-            xtask_dir.join("src/main.rs"),
-            include_str!("../../templates/xtask/main.rs"),
-        )?;
-
-        self.render_file(
-            &xtask_dir.join("Cargo.toml"),
-            include_str!("../../templates/xtask/cargo_xtask.txt"),
-            context,
-        )?;
-
-        // workspace Cargo.toml
-        self.render_file(
-            &target_path.join("Cargo.toml"),
-            include_str!("../../templates/xtask/cargo_workspace.txt"),
-            context,
-        )?;
-
-        // xtask alias
-        let cargo_config_dir = target_path.join(".cargo");
-
-        fs::create_dir_all(&cargo_config_dir)?;
-        fs::write(
-            cargo_config_dir.join("config.toml"),
-            include_str!("../../templates/xtask/cargo_config.toml"),
-        )?;
-
-        Ok(())
+    fn render_str(&self, source: &str, context: &Value) -> Result<String> {
+        self.env
+            .render_str(source, context)
+            .with_context(|| format!("Failed to render '{}'", source))
     }
 }
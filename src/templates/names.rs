@@ -0,0 +1,395 @@
+/// The forms of a project name the templates need, all derived from the name the user typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectName {
+    /// The package name and project directory, e.g. `my-cool-plugin`.
+    pub crate_name: String,
+    /// The Rust module name, e.g. `my_cool_plugin`.
+    pub module_name: String,
+    /// The plugin struct name, e.g. `MyCoolPlugin`.
+    pub type_name: String,
+    /// The human readable name, e.g. `My Cool Plugin`.
+    pub display_name: String,
+}
+
+/// Rust keywords, including reserved and edition specific ones. None of these can be a module
+/// name.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "union", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Crate names that clash with the standard library, the generated workspace, nih-plug itself,
+/// Cargo's build directories or reserved Windows file names.
+const RESERVED_CRATE_NAMES: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "test",
+    "xtask",
+    "nih_plug",
+    "nih_plug_xtask",
+    "nih_plug_iced",
+    "nih_plug_egui",
+    "nih_plug_vizia",
+    "build",
+    "deps",
+    "examples",
+    "incremental",
+    "con",
+    "prn",
+    "aux",
+    "nul",
+    "com1",
+    "com2",
+    "com3",
+    "com4",
+    "lpt1",
+    "lpt2",
+    "lpt3",
+];
+
+/// Types from the nih-plug prelude the plugin struct would shadow.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Plugin",
+    "Params",
+    "Editor",
+    "Buffer",
+    "ClapPlugin",
+    "Vst3Plugin",
+    "FloatParam",
+    "IntParam",
+    "BoolParam",
+    "EnumParam",
+    "NoteEvent",
+    "Smoother",
+    "ProcessStatus",
+];
+
+impl ProjectName {
+    /// Parses a name in any case style, e.g. `my-cool-plugin`, `my_cool_plugin`, `MyCoolPlugin`
+    /// or `My Cool Plugin`. Names that would not compile are rejected, with a suggestion when
+    /// there is an obvious fix.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::check(name).map_err(|message| with_suggestion(message, suggest(&split_words(name))))
+    }
+
+    fn check(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("The project name cannot be empty.".to_string());
+        }
+
+        if let Some(c) = name
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' ')))
+        {
+            return Err(format!(
+                "The project name may only contain ASCII letters, digits, '-', '_' and spaces, not '{}'.",
+                c
+            ));
+        }
+
+        let words = split_words(name);
+        if words.is_empty() {
+            return Err("The project name must contain letters.".to_string());
+        }
+
+        let project_name = Self::from_words(&words);
+
+        if project_name
+            .module_name
+            .starts_with(|c: char| c.is_ascii_digit())
+        {
+            return Err("The project name must start with a letter.".to_string());
+        }
+
+        if KEYWORDS.contains(&project_name.module_name.as_str()) {
+            return Err(format!(
+                "'{}' is a Rust keyword and can't be used as a module name.",
+                project_name.module_name
+            ));
+        }
+
+        if RESERVED_CRATE_NAMES.contains(&project_name.module_name.as_str()) {
+            return Err(format!(
+                "'{}' is a reserved crate name.",
+                project_name.crate_name
+            ));
+        }
+
+        if RESERVED_TYPE_NAMES.contains(&project_name.type_name.as_str()) {
+            return Err(format!(
+                "'{}' clashes with a type from the nih-plug prelude.",
+                project_name.type_name
+            ));
+        }
+
+        Ok(project_name)
+    }
+
+    fn from_words(words: &[String]) -> Self {
+        Self {
+            crate_name: join_lowercase(words, "-"),
+            module_name: join_lowercase(words, "_"),
+            type_name: words.iter().map(|word| capitalize(word)).collect(),
+            display_name: display_words(words).join(" "),
+        }
+    }
+}
+
+/// A similar name that passes validation, if there is one.
+fn suggest(words: &[String]) -> Option<String> {
+    let mut words: Vec<_> = words
+        .iter()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    // move leading digits to the end, `2band-eq` becomes `band-eq-2`
+    if let Some(first) = words.first_mut() {
+        let digits: String = first.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if !digits.is_empty() {
+            first.replace_range(..digits.len(), "");
+            if first.is_empty() {
+                words.remove(0);
+            }
+            words.push(digits);
+        }
+    }
+
+    let name = join_lowercase(&words, "-");
+    let mut candidates = vec![name.clone()];
+    if !name.ends_with("plugin") {
+        candidates.push(format!("{}-plugin", name));
+    }
+    candidates.push(format!("my-{}", name));
+
+    candidates
+        .into_iter()
+        .find(|candidate| ProjectName::check(candidate).is_ok())
+}
+
+fn with_suggestion(message: String, suggestion: Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!("{} Try '{}'.", message, suggestion),
+        None => message,
+    }
+}
+
+/// Splits a name in any case style into words, keeping their case. `MyHTTPPlugin2-beta` becomes
+/// `["My", "HTTP", "Plugin2", "beta"]`.
+pub fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+
+    for c in s.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev = None;
+            continue;
+        }
+
+        if let Some(prev) = prev {
+            if c.is_uppercase() && (prev.is_lowercase() || prev.is_numeric()) {
+                words.push(std::mem::take(&mut current));
+            } else if c.is_lowercase() && prev.is_uppercase() && current.chars().count() > 1 {
+                // the last capital of an acronym starts the next word
+                current.pop();
+                words.push(std::mem::replace(&mut current, prev.to_string()));
+            }
+        }
+
+        current.push(c);
+        prev = Some(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn join_lowercase(words: &[String], separator: &str) -> String {
+    let words: Vec<_> = words.iter().map(|word| word.to_lowercase()).collect();
+
+    words.join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Capitalizes lowercase words and keeps words that already have capitals, so acronyms like
+/// `EQ` survive. An all uppercase name is capitalized word by word instead.
+fn display_words(words: &[String]) -> Vec<String> {
+    let all_uppercase = words
+        .iter()
+        .all(|word| !word.chars().any(char::is_lowercase));
+
+    words
+        .iter()
+        .map(|word| {
+            if !all_uppercase && word.chars().any(char::is_uppercase) {
+                word.clone()
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect()
+}
+
+pub fn snake_case(value: &str) -> String {
+    join_lowercase(&split_words(value), "_")
+}
+
+pub fn kebab_case(value: &str) -> String {
+    join_lowercase(&split_words(value), "-")
+}
+
+pub fn camel_case(value: &str) -> String {
+    split_words(value)
+        .iter()
+        .map(|word| capitalize(word))
+        .collect()
+}
+
+pub fn title_case(value: &str) -> String {
+    display_words(&split_words(value)).join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_every_case_style() {
+        for name in [
+            "my-cool-plugin",
+            "my_cool_plugin",
+            "MyCoolPlugin",
+            "myCoolPlugin",
+            "My Cool Plugin",
+            "MY_COOL_PLUGIN",
+            "  my--cool__plugin ",
+        ] {
+            let words: Vec<_> = split_words(name)
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect();
+            assert_eq!(words, ["my", "cool", "plugin"], "{}", name);
+        }
+    }
+
+    #[test]
+    fn splits_acronyms_and_digits() {
+        assert_eq!(split_words("MyHTTPPlugin2"), ["My", "HTTP", "Plugin2"]);
+        assert_eq!(split_words("eq2Band"), ["eq2", "Band"]);
+        assert_eq!(split_words("SuperEQ"), ["Super", "EQ"]);
+    }
+
+    #[test]
+    fn derives_all_forms() {
+        for name in ["my-cool-plugin", "MyCoolPlugin", "my cool plugin"] {
+            assert_eq!(
+                ProjectName::parse(name).unwrap(),
+                ProjectName {
+                    crate_name: "my-cool-plugin".to_string(),
+                    module_name: "my_cool_plugin".to_string(),
+                    type_name: "MyCoolPlugin".to_string(),
+                    display_name: "My Cool Plugin".to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn display_name_keeps_acronyms() {
+        let name = ProjectName::parse("SuperEQ").unwrap();
+        assert_eq!(name.crate_name, "super-eq");
+        assert_eq!(name.type_name, "SuperEq");
+        assert_eq!(name.display_name, "Super EQ");
+
+        let name = ProjectName::parse("MY_COOL_PLUGIN").unwrap();
+        assert_eq!(name.display_name, "My Cool Plugin");
+    }
+
+    #[test]
+    fn rejects_empty_names() {
+        assert!(ProjectName::parse("").is_err());
+        assert!(ProjectName::parse("   ").is_err());
+        assert!(ProjectName::parse("--").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_characters_with_suggestion() {
+        let error = ProjectName::parse("my.plugin!").unwrap_err();
+        assert!(error.contains("'.'"), "{}", error);
+        assert!(error.contains("Try 'my-plugin'"), "{}", error);
+
+        assert!(ProjectName::parse("plügin").is_err());
+    }
+
+    #[test]
+    fn rejects_leading_digits_with_suggestion() {
+        let error = ProjectName::parse("2band-eq").unwrap_err();
+        assert!(error.contains("start with a letter"), "{}", error);
+        assert!(error.contains("Try 'band-eq-2'"), "{}", error);
+
+        let error = ProjectName::parse("808").unwrap_err();
+        assert!(error.contains("Try 'my-808'"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_keywords_with_suggestion() {
+        for name in ["match", "type", "Self", "async", "crate"] {
+            let error = ProjectName::parse(name).unwrap_err();
+            assert!(error.contains("keyword"), "{}", error);
+            assert!(
+                error.contains(&format!("Try '{}-plugin'", name.to_lowercase())),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        for name in ["std", "xtask", "nih-plug", "nih_plug_iced", "con"] {
+            let error = ProjectName::parse(name).unwrap_err();
+            assert!(error.contains("reserved"), "{}", error);
+            assert!(error.contains("-plugin'"), "{}", error);
+        }
+
+        let error = ProjectName::parse("plugin").unwrap_err();
+        assert!(error.contains("nih-plug prelude"), "{}", error);
+        assert!(error.contains("Try 'my-plugin'"), "{}", error);
+    }
+
+    #[test]
+    fn case_filters() {
+        assert_eq!(snake_case("MyCoolPlugin"), "my_cool_plugin");
+        assert_eq!(kebab_case("my_cool_plugin"), "my-cool-plugin");
+        assert_eq!(camel_case("my-cool-plugin"), "MyCoolPlugin");
+        assert_eq!(title_case("my-cool-plugin"), "My Cool Plugin");
+    }
+}
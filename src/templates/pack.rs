@@ -0,0 +1,315 @@
+use crate::config::Config;
+use crate::templates::{GuiFramework, PluginKind};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The manifest file every template pack directory contains.
+pub const MANIFEST_FILE: &str = "template.toml";
+
+/// The name the bundled templates are available under.
+pub const BUILTIN_NAME: &str = "builtin";
+
+pub const BUILTIN_MANIFEST: &str = include_str!("../../templates/template.toml");
+
+/// Describes the files a template pack generates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub description: String,
+    /// Whether the built-in project files are generated as well. Pack files with the same target
+    /// path replace the built-in ones.
+    #[serde(default = "default_true")]
+    pub include_builtin: bool,
+    /// Built-in variables the pack's templates use, such as `project_name` or `config.vendor`.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// The only GUI framework the pack supports, for packs exported for a single framework.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gui: Option<GuiFramework>,
+    /// The only plugin kind the pack supports, for packs exported for a single kind.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<PluginKind>,
+    /// Extra variables the pack's templates can use, with their values.
+    #[serde(default)]
+    pub variables: toml::Table,
+    #[serde(default)]
+    pub files: Vec<FileSpec>,
+}
+
+/// A single generated file. `path` and `template` are rendered like templates themselves, and
+/// `when` is an expression that skips the file when it is false.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileSpec {
+    pub path: String,
+    pub template: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+}
+
+/// A set of templates with their manifest, either bundled or loaded from a directory.
+#[derive(Debug, Clone)]
+pub struct TemplatePack {
+    pub name: String,
+    /// The pack directory. `None` for the built-in pack.
+    pub dir: Option<PathBuf>,
+    pub manifest: Manifest,
+}
+
+impl TemplatePack {
+    pub fn builtin() -> Self {
+        let manifest =
+            toml::from_str(BUILTIN_MANIFEST).expect("the built-in template manifest is valid");
+
+        Self {
+            name: BUILTIN_NAME.to_string(),
+            dir: None,
+            manifest,
+        }
+    }
+
+    /// The built-in pack limited to a single GUI framework or plugin kind.
+    pub fn builtin_choice(choice: BuiltinChoice) -> Self {
+        let mut pack = Self::builtin();
+        pack.name = choice.name();
+        pack.manifest.description = choice.description();
+        pack.manifest.files = pack
+            .manifest
+            .files
+            .into_iter()
+            .filter_map(|file| {
+                Some(FileSpec {
+                    template: choice.template(&file.template)?,
+                    ..file
+                })
+            })
+            .collect();
+        choice.restrict(&mut pack.manifest);
+
+        pack
+    }
+
+    /// Checks that the pack supports the selected GUI framework and plugin kind.
+    pub fn check_choice(&self, gui: GuiFramework, kind: PluginKind) -> Result<()> {
+        if let Some(pack_gui) = self.manifest.gui
+            && pack_gui != gui
+        {
+            anyhow::bail!(
+                "Template pack '{}' only supports the {} GUI. Pass --gui {}.",
+                self.name,
+                pack_gui.label(),
+                BuiltinChoice::Gui(pack_gui).name()
+            );
+        }
+
+        if let Some(pack_kind) = self.manifest.kind
+            && pack_kind != kind
+        {
+            anyhow::bail!(
+                "Template pack '{}' only supports the {} kind. Pass --kind {}.",
+                self.name,
+                pack_kind.label(),
+                BuiltinChoice::Kind(pack_kind).name()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Loads the pack in `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let content = fs::read_to_string(&manifest_path).with_context(|| {
+            format!(
+                "Failed to read template manifest: {}",
+                manifest_path.display()
+            )
+        })?;
+        let manifest = toml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse template manifest: {}",
+                manifest_path.display()
+            )
+        })?;
+
+        let name = dir
+            .canonicalize()
+            .ok()
+            .and_then(|dir| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| dir.display().to_string());
+
+        Ok(Self {
+            name,
+            dir: Some(dir.to_path_buf()),
+            manifest,
+        })
+    }
+
+    /// Finds a pack by directory path, by the built-in name, by name in the configured templates
+    /// directory, or by a built-in GUI framework or plugin kind.
+    pub fn resolve(name: &str, config: &Config) -> Result<Self> {
+        let path = Path::new(name);
+        if path.join(MANIFEST_FILE).is_file() {
+            return Self::load(path);
+        }
+
+        if name == BUILTIN_NAME {
+            return Ok(Self::builtin());
+        }
+
+        if let Some(templates_dir) = config.templates_dir() {
+            let path = templates_dir.join(name);
+            if path.join(MANIFEST_FILE).is_file() {
+                return Self::load(&path);
+            }
+        }
+
+        if let Some(choice) = BuiltinChoice::parse(name) {
+            return Ok(Self::builtin_choice(choice));
+        }
+
+        anyhow::bail!(
+            "Template pack '{}' not found. Pass a directory containing a {}, a GUI framework or plugin kind from `nih-bootstrap templates list`, or the name of a pack in {}.",
+            name,
+            MANIFEST_FILE,
+            config
+                .templates_dir()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "the templates directory".to_string())
+        )
+    }
+
+    /// Every pack in the configured templates directory. Packs that fail to load are returned as
+    /// errors so they can be reported without hiding the others.
+    pub fn user_packs(config: &Config) -> Vec<Result<Self>> {
+        let Some(entries) = config
+            .templates_dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut dirs: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.join(MANIFEST_FILE).is_file())
+            .collect();
+        dirs.sort();
+
+        dirs.iter().map(|dir| Self::load(dir)).collect()
+    }
+
+    /// The files to generate. Built-in files come first, replaced by pack files with the same
+    /// target path.
+    pub fn files(&self) -> Vec<FileSpec> {
+        if self.dir.is_none() || !self.manifest.include_builtin {
+            return self.manifest.files.clone();
+        }
+
+        let mut files = Self::builtin().manifest.files;
+        for file in &self.manifest.files {
+            match files.iter_mut().find(|builtin| builtin.path == file.path) {
+                Some(builtin) => *builtin = file.clone(),
+                None => files.push(file.clone()),
+            }
+        }
+
+        files
+    }
+}
+
+/// A single built-in GUI framework or plugin kind, by the name `templates list` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinChoice {
+    Gui(GuiFramework),
+    Kind(PluginKind),
+}
+
+impl BuiltinChoice {
+    pub fn parse(name: &str) -> Option<Self> {
+        GuiFramework::from_str(name, true)
+            .map(BuiltinChoice::Gui)
+            .or_else(|_| PluginKind::from_str(name, true).map(BuiltinChoice::Kind))
+            .ok()
+    }
+
+    /// The command line value, e.g. `iced` or `midi`.
+    pub fn name(&self) -> String {
+        let value = match self {
+            BuiltinChoice::Gui(gui) => gui.to_possible_value(),
+            BuiltinChoice::Kind(kind) => kind.to_possible_value(),
+        };
+
+        value
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    fn description(&self) -> String {
+        match self {
+            BuiltinChoice::Gui(GuiFramework::None) => {
+                "The built-in templates for a plugin without an editor".to_string()
+            }
+            BuiltinChoice::Gui(gui) => {
+                format!("The built-in templates with the {} editor", gui.label())
+            }
+            BuiltinChoice::Kind(kind) => {
+                format!("The built-in templates for the {} kind", kind.label())
+            }
+        }
+    }
+
+    /// The variable built-in manifests select this choice's templates with.
+    fn placeholder(&self) -> &'static str {
+        match self {
+            BuiltinChoice::Gui(_) => "{{ gui }}",
+            BuiltinChoice::Kind(_) => "{{ kind }}",
+        }
+    }
+
+    /// The template a built-in file uses with this choice, or `None` when the file isn't
+    /// generated at all, e.g. the editor of a headless plugin.
+    pub fn template(&self, template: &str) -> Option<String> {
+        if !template.contains(self.placeholder()) {
+            return Some(template.to_string());
+        }
+        if *self == BuiltinChoice::Gui(GuiFramework::None) {
+            return None;
+        }
+
+        Some(template.replace(self.placeholder(), &self.name()))
+    }
+
+    /// Whether a built-in template is only used by another choice, e.g. `project/editor_egui.txt`
+    /// for iced.
+    pub fn excludes(&self, template: &str) -> bool {
+        TemplatePack::builtin().manifest.files.iter().any(|file| {
+            let Some((prefix, suffix)) = file.template.split_once(self.placeholder()) else {
+                return false;
+            };
+
+            template.starts_with(prefix)
+                && template.ends_with(suffix)
+                && Some(template.to_string()) != self.template(&file.template)
+        })
+    }
+
+    /// Limits `manifest` to this choice.
+    pub fn restrict(&self, manifest: &mut Manifest) {
+        match *self {
+            BuiltinChoice::Gui(gui) => manifest.gui = Some(gui),
+            BuiltinChoice::Kind(kind) => manifest.kind = Some(kind),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A rendered file that has not been written yet.
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    /// The path relative to the project directory.
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Every file of a rendered project, kept in memory until [`FilePlan::write`] puts them on disk.
+#[derive(Debug, Clone, Default)]
+pub struct FilePlan {
+    pub files: Vec<PlannedFile>,
+}
+
+impl FilePlan {
+    /// Adds a file, replacing an earlier file with the same path.
+    pub fn add(&mut self, path: PathBuf, content: String) {
+        match self.files.iter_mut().find(|file| file.path == path) {
+            Some(file) => file.content = content,
+            None => self.files.push(PlannedFile { path, content }),
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&PlannedFile> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.files.retain(|file| file.path != path);
+    }
+
+    /// The planned files that already exist below `target_path`.
+    pub fn collisions(&self, target_path: &Path) -> Vec<&PlannedFile> {
+        self.files
+            .iter()
+            .filter(|file| target_path.join(&file.path).exists())
+            .collect()
+    }
+
+    /// Creates `target_path` with every file in it. The files are written to a staging directory
+    /// next to the target first, which is renamed into place once everything was written, so a
+    /// failure never leaves a half-created project behind.
+    pub fn write(&self, target_path: &Path) -> Result<()> {
+        let parent = match target_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let name = target_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+
+        // removed again when dropped, unless it was renamed into place
+        let prefix = format!(".{}.", name);
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(&prefix);
+        // temporary directories are private by default, the project should get the usual
+        // directory permissions instead. The umask still applies, and nobody else can write
+        // to the directory while it is being filled
+        #[cfg(unix)]
+        builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o755));
+        let staging = builder.tempdir_in(parent).with_context(|| {
+            format!(
+                "Failed to create a staging directory in {}",
+                parent.display()
+            )
+        })?;
+
+        for file in &self.files {
+            write_file(&staging.path().join(&file.path), &file.content)
+                .with_context(|| format!("Failed to write {}", file.path.display()))?;
+        }
+
+        fs::rename(staging.path(), target_path).with_context(|| {
+            format!(
+                "Failed to move the project into place at {}",
+                target_path.display()
+            )
+        })?;
+        let _ = staging.keep();
+
+        Ok(())
+    }
+
+    /// Writes every file into the existing directory `target_path`, overwriting files that are
+    /// already there. If a file can't be written, the files and directories written so far are
+    /// restored or removed again.
+    pub fn write_into(&self, target_path: &Path) -> Result<()> {
+        let mut undo = Vec::new();
+
+        for file in &self.files {
+            if let Err(e) = write_tracked(&target_path.join(&file.path), &file.content, &mut undo) {
+                for step in undo.iter().rev() {
+                    step.revert();
+                }
+
+                return Err(e).with_context(|| format!("Failed to write {}", file.path.display()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Formats the plan as a directory tree with file sizes, rooted at `root`.
+    pub fn tree(&self, root: &str) -> String {
+        let mut tree = Tree::default();
+        for file in &self.files {
+            let mut node = &mut tree;
+            for component in file.path.iter() {
+                node = node
+                    .children
+                    .entry(component.to_string_lossy().to_string())
+                    .or_default();
+            }
+            node.size = Some(file.content.len());
+        }
+
+        let mut output = format!("{}/\n", root);
+        tree.format("", &mut output);
+        output
+    }
+}
+
+#[derive(Default)]
+struct Tree {
+    /// The file size, `None` for directories.
+    size: Option<usize>,
+    children: BTreeMap<String, Tree>,
+}
+
+impl Tree {
+    fn format(&self, prefix: &str, output: &mut String) {
+        let count = self.children.len();
+
+        for (i, (name, child)) in self.children.iter().enumerate() {
+            let last = i + 1 == count;
+            let branch = if last { "└── " } else { "├── " };
+
+            match child.size {
+                Some(size) => output.push_str(&format!(
+                    "{}{}{} ({})\n",
+                    prefix,
+                    branch,
+                    name,
+                    format_size(size)
+                )),
+                None => {
+                    output.push_str(&format!("{}{}{}/\n", prefix, branch, name));
+                    let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                    child.format(&prefix, output);
+                }
+            }
+        }
+    }
+}
+
+/// A change made by [`FilePlan::write_into`] that can be reverted.
+enum Undo {
+    RemoveDir(PathBuf),
+    RemoveFile(PathBuf),
+    RestoreFile(PathBuf, Vec<u8>),
+}
+
+impl Undo {
+    fn revert(&self) {
+        // best effort, the original error is what gets reported
+        let _ = match self {
+            Undo::RemoveDir(path) => fs::remove_dir(path),
+            Undo::RemoveFile(path) => fs::remove_file(path),
+            Undo::RestoreFile(path, content) => fs::write(path, content),
+        };
+    }
+}
+
+fn write_tracked(path: &Path, content: &str, undo: &mut Vec<Undo>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        let mut missing: Vec<_> = parent.ancestors().take_while(|dir| !dir.exists()).collect();
+        missing.reverse();
+
+        for dir in missing {
+            fs::create_dir(dir)?;
+            undo.push(Undo::RemoveDir(dir.to_path_buf()));
+        }
+    }
+
+    match fs::read(path) {
+        Ok(previous) => undo.push(Undo::RestoreFile(path.to_path_buf(), previous)),
+        Err(_) if !path.exists() => undo.push(Undo::RemoveFile(path.to_path_buf())),
+        Err(e) => return Err(e.into()),
+    }
+    fs::write(path, content)?;
+
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+
+    Ok(())
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
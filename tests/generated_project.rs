@@ -0,0 +1,781 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// `nih-bootstrap` with `args`, run in a scratch directory and isolated from any user or local
+/// config.
+fn cli(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_nih-bootstrap"));
+    command
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"));
+    command
+}
+
+fn run_cli(dir: &Path, args: &[&str]) -> Output {
+    cli(dir, args)
+        .output()
+        .expect("failed to run nih-bootstrap")
+}
+
+/// Runs `nih-bootstrap init` in a scratch directory.
+fn init_project(dir: &Path, args: &[&str]) -> PathBuf {
+    let output = run_cli(dir, &[&["init"], args].concat());
+
+    assert!(
+        output.status.success(),
+        "nih-bootstrap init failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    dir.join(args[0])
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Checks that no placeholder or template syntax survived rendering, that the Rust sources parse
+/// and that the TOML files are valid.
+fn check_project(project: &Path) {
+    let mut files = Vec::new();
+    collect_files(project, &mut files);
+    assert!(!files.is_empty(), "no files were generated");
+
+    for file in files {
+        let content = fs::read_to_string(&file).unwrap();
+
+        assert!(
+            !content.contains("%%"),
+            "{} contains an unreplaced placeholder",
+            file.display()
+        );
+        assert!(
+            !content.contains("{{") && !content.contains("{%"),
+            "{} contains unrendered template syntax",
+            file.display()
+        );
+
+        match file.extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => {
+                if let Err(e) = syn::parse_file(&content) {
+                    panic!("{} is not valid Rust: {}", file.display(), e);
+                }
+            }
+            Some("toml") => {
+                if let Err(e) = content.parse::<toml::Table>() {
+                    panic!("{} is not valid TOML: {}", file.display(), e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn iced_project_renders_valid_rust() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(
+        dir.path(),
+        &["test-plugin", "--formats", "vst3,clap,standalone"],
+    );
+
+    check_project(&project);
+
+    let lib = fs::read_to_string(project.join("test_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains("impl Vst3Plugin for TestPlugin"));
+    assert!(lib.contains("impl ClapPlugin for TestPlugin"));
+    assert!(project.join("test_plugin/src/editor.rs").exists());
+    assert!(project.join("test_plugin/src/main.rs").exists());
+}
+
+#[test]
+fn egui_project_renders_valid_rust() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin", "--gui", "egui"]);
+
+    check_project(&project);
+
+    let editor = fs::read_to_string(project.join("test_plugin/src/editor.rs")).unwrap();
+    assert!(editor.contains("create_egui_editor"));
+    let manifest = fs::read_to_string(project.join("test_plugin/Cargo.toml")).unwrap();
+    assert!(manifest.contains("nih_plug_egui"));
+    assert!(!manifest.contains("nih_plug_iced"));
+}
+
+#[test]
+fn vizia_project_renders_valid_rust() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin", "--gui", "vizia"]);
+
+    check_project(&project);
+
+    let editor = fs::read_to_string(project.join("test_plugin/src/editor.rs")).unwrap();
+    assert!(editor.contains("create_vizia_editor"));
+    assert!(editor.contains("ResizeHandle::new(cx)"));
+    let manifest = fs::read_to_string(project.join("test_plugin/Cargo.toml")).unwrap();
+    assert!(manifest.contains("nih_plug_vizia"));
+}
+
+#[test]
+fn headless_project_has_no_editor() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin", "--gui", "none"]);
+
+    check_project(&project);
+
+    assert!(!project.join("test_plugin/src/editor.rs").exists());
+    let lib = fs::read_to_string(project.join("test_plugin/src/lib.rs")).unwrap();
+    assert!(!lib.contains("mod editor"));
+    let manifest = fs::read_to_string(project.join("test_plugin/Cargo.toml")).unwrap();
+    assert!(!manifest.contains("nih_plug_iced"));
+}
+
+#[test]
+fn every_kind_renders_valid_rust() {
+    for kind in ["effect", "instrument", "midi", "analyzer"] {
+        for gui in ["iced", "none"] {
+            let dir = tempfile::tempdir().unwrap();
+            let project = init_project(
+                dir.path(),
+                &[
+                    "test-plugin",
+                    "--kind",
+                    kind,
+                    "--gui",
+                    gui,
+                    "--formats",
+                    "vst3,clap,standalone",
+                ],
+            );
+
+            check_project(&project);
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin", "--kind", "instrument"]);
+    let lib = fs::read_to_string(project.join("test_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains("const MIDI_INPUT: MidiConfig = MidiConfig::Basic;"));
+    assert!(lib.contains("NoteEvent::NoteOn"));
+}
+
+#[test]
+fn midi_kind_with_default_formats_has_audio_ports() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin", "--kind", "midi"]);
+    check_project(&project);
+
+    // the default formats include the standalone, which can't run without audio I/O
+    assert!(project.join("test_plugin/src/main.rs").is_file());
+    let lib = fs::read_to_string(project.join("test_plugin/src/lib.rs")).unwrap();
+    assert!(!lib.contains("AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[];"));
+    assert!(lib.contains("main_input_channels: NonZeroU32::new(2)"));
+    assert!(lib.contains("main_output_channels: NonZeroU32::new(2)"));
+}
+
+#[test]
+fn quotes_and_backslashes_are_escaped() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(
+        dir.path(),
+        &[
+            "test-plugin",
+            "--description",
+            "A \"quoted\" C:\\path",
+            "--vendor",
+            "O\"Neil \\ Sons",
+            "--authors",
+            "Jo \"JJ\" Doe",
+            "--formats",
+            "vst3,clap",
+        ],
+    );
+
+    check_project(&project);
+
+    let lib = fs::read_to_string(project.join("test_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains(r#"Some("A \"quoted\" C:\\path")"#));
+    let manifest: toml::Table = fs::read_to_string(project.join("test_plugin/Cargo.toml"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        manifest["package"]["description"].as_str(),
+        Some("A \"quoted\" C:\\path")
+    );
+}
+
+#[test]
+fn template_pack_adds_and_overrides_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("team-pack");
+    fs::create_dir_all(pack.join("project")).unwrap();
+    fs::write(
+        pack.join("template.toml"),
+        r#"
+requires = ["project_name"]
+
+[variables]
+license = "MIT"
+
+[[files]]
+path = "LICENSE"
+template = "license.txt"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        pack.join("license.txt"),
+        "{{ license }} for {{ project_name }}\n",
+    )
+    .unwrap();
+    fs::write(
+        pack.join("project/main.txt"),
+        "// team main\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let project = init_project(dir.path(), &["test-plugin", "--template", "team-pack"]);
+
+    check_project(&project);
+    assert_eq!(
+        fs::read_to_string(project.join("LICENSE")).unwrap(),
+        "MIT for test-plugin\n"
+    );
+    let main = fs::read_to_string(project.join("test_plugin/src/main.rs")).unwrap();
+    assert!(main.starts_with("// team main"));
+    assert!(project.join("test_plugin/src/lib.rs").exists());
+}
+
+#[test]
+fn template_pack_with_unknown_variable_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("pack");
+    fs::create_dir_all(&pack).unwrap();
+    fs::write(
+        pack.join("template.toml"),
+        "requires = [\"plugin_color\"]\n",
+    )
+    .unwrap();
+
+    let output = run_cli(dir.path(), &["init", "test-plugin", "--template", "pack"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("plugin_color"));
+    assert!(!dir.path().join("test-plugin").exists());
+}
+
+#[test]
+fn exported_builtin_pack_renders_the_same_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["templates", "export", "builtin", "exported"]);
+    assert!(output.status.success());
+
+    let exported_dir = tempfile::tempdir().unwrap();
+    let builtin = init_project(dir.path(), &["test-plugin", "--gui", "egui"]);
+    let exported = init_project(
+        exported_dir.path(),
+        &[
+            "test-plugin",
+            "--gui",
+            "egui",
+            "--template",
+            dir.path().join("exported").to_str().unwrap(),
+        ],
+    );
+
+    let mut files = Vec::new();
+    collect_files(&builtin, &mut files);
+    for file in files {
+        let relative = file.strip_prefix(&builtin).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            fs::read_to_string(exported.join(relative)).unwrap(),
+            "{} differs",
+            relative.display()
+        );
+    }
+}
+
+#[test]
+fn pack_is_not_exported_into_itself() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("mypack");
+    fs::create_dir_all(&pack).unwrap();
+    fs::write(pack.join("template.toml"), "description = \"Mine\"\n").unwrap();
+
+    for target in ["mypack/out", "./mypack/nested/../out", "mypack"] {
+        let output = run_cli(dir.path(), &["templates", "export", "./mypack", target]);
+
+        assert!(!output.status.success(), "{}", target);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("into its own directory"));
+    }
+    assert!(!pack.join("out").exists());
+}
+
+#[test]
+fn builtin_gui_and_kind_can_be_shown_and_exported() {
+    let dir = tempfile::tempdir().unwrap();
+    let templates = |args: &[&str]| run_cli(dir.path(), &[&["templates"], args].concat());
+
+    let output = templates(&["show", "iced"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("project/editor_iced.txt"));
+    assert!(!stdout.contains("editor_egui"));
+
+    let output = templates(&["show", "none"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("editor.rs"));
+
+    assert!(templates(&["export", "egui", "egui-pack"]).status.success());
+    let pack = dir.path().join("egui-pack");
+    assert!(pack.join("project/editor_egui.txt").is_file());
+    assert!(!pack.join("project/editor_iced.txt").exists());
+    assert!(pack.join("project/lib_midi.txt").is_file());
+
+    let exported = init_project(
+        dir.path(),
+        &[
+            "test-plugin",
+            "--gui",
+            "egui",
+            "--kind",
+            "midi",
+            "--template",
+            pack.to_str().unwrap(),
+        ],
+    );
+    check_project(&exported);
+    assert!(
+        fs::read_to_string(exported.join("test_plugin/src/editor.rs"))
+            .unwrap()
+            .contains("nih_plug_egui")
+    );
+
+    // the pack only has the egui editor
+    let output = run_cli(
+        dir.path(),
+        &["init", "other-plugin", "--template", pack.to_str().unwrap()],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Pass --gui egui"));
+    assert!(!dir.path().join("other-plugin").exists());
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |args: &[&str]| run_cli(dir.path(), &[&["init"], args].concat());
+
+    let output = run(&["test-plugin", "--dry-run"]);
+    assert!(output.status.success());
+    let tree = String::from_utf8_lossy(&output.stdout);
+    assert!(tree.contains("lib.rs ("));
+    assert!(tree.contains("editor.rs ("));
+
+    let output = run(&["test-plugin", "--show", "test_plugin/Cargo.toml"]);
+    assert!(output.status.success());
+    let manifest = String::from_utf8(output.stdout).unwrap();
+    assert!(manifest.starts_with("[package]"));
+
+    assert!(
+        !run(&["test-plugin", "--show", "missing.rs"])
+            .status
+            .success()
+    );
+    assert!(!dir.path().join("test-plugin").exists());
+
+    let project = init_project(dir.path(), &["test-plugin"]);
+    assert_eq!(
+        fs::read_to_string(project.join("test_plugin/Cargo.toml")).unwrap(),
+        manifest
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn project_directory_is_not_world_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["test-plugin"]);
+    let mode = fs::metadata(&project).unwrap().permissions().mode();
+
+    assert_eq!(mode & 0o022, 0, "mode is {:o}", mode);
+    assert_eq!(mode & 0o700, 0o700, "mode is {:o}", mode);
+}
+
+#[test]
+fn failed_init_leaves_nothing_behind() {
+    let dir = tempfile::tempdir().unwrap();
+    let pack = dir.path().join("pack");
+    fs::create_dir_all(&pack).unwrap();
+    // the second file needs `notes` to be a directory, which fails after the first was written
+    fs::write(
+        pack.join("template.toml"),
+        r#"
+include_builtin = false
+
+[[files]]
+path = "notes"
+template = "notes.txt"
+
+[[files]]
+path = "notes/todo.txt"
+template = "notes.txt"
+"#,
+    )
+    .unwrap();
+    fs::write(pack.join("notes.txt"), "{{ project_name }}\n").unwrap();
+
+    let output = run_cli(dir.path(), &["init", "test-plugin", "--template", "pack"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("notes/todo.txt"));
+
+    let mut entries: Vec<_> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    entries.sort();
+    assert_eq!(entries, ["pack"]);
+}
+
+#[test]
+fn init_into_existing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("test-plugin");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("README.md"), "# Test plugin\n").unwrap();
+
+    init_project(dir.path(), &["test-plugin"]);
+
+    check_project(&project);
+    assert_eq!(
+        fs::read_to_string(project.join("README.md")).unwrap(),
+        "# Test plugin\n"
+    );
+}
+
+#[test]
+fn existing_files_need_force() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("test-plugin");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("Cargo.toml"), "# mine\n").unwrap();
+
+    let output = run_cli(dir.path(), &["init", "test-plugin"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    assert_eq!(
+        fs::read_to_string(project.join("Cargo.toml")).unwrap(),
+        "# mine\n"
+    );
+    assert!(!project.join("xtask").exists());
+
+    init_project(dir.path(), &["test-plugin", "--force"]);
+
+    check_project(&project);
+    assert!(
+        fs::read_to_string(project.join("Cargo.toml"))
+            .unwrap()
+            .contains("[workspace]")
+    );
+}
+
+#[test]
+fn existing_ci_workflow_needs_force() {
+    let dir = tempfile::tempdir().unwrap();
+    let workflow = dir
+        .path()
+        .join("test-plugin/.github/workflows/general.yaml");
+    fs::create_dir_all(workflow.parent().unwrap()).unwrap();
+    fs::write(&workflow, "# mine\n").unwrap();
+
+    let output = run_cli(dir.path(), &["init", "test-plugin", "--git", "--ci"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(".github/workflows/general.yaml"));
+    assert_eq!(fs::read_to_string(&workflow).unwrap(), "# mine\n");
+
+    init_project(dir.path(), &["test-plugin", "--git", "--ci", "--force"]);
+    assert!(
+        fs::read_to_string(&workflow)
+            .unwrap()
+            .contains("libasound2-dev")
+    );
+}
+
+#[test]
+fn failed_init_into_existing_directory_is_rolled_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("test-plugin");
+    // a directory where the workspace manifest should go makes that write fail
+    fs::create_dir_all(project.join("Cargo.toml")).unwrap();
+    fs::write(project.join("README.md"), "# Test plugin\n").unwrap();
+
+    let output = run_cli(dir.path(), &["init", "test-plugin", "--force"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cargo.toml"));
+
+    let mut entries: Vec<_> = fs::read_dir(&project)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    entries.sort();
+    assert_eq!(entries, ["Cargo.toml", "README.md"]);
+}
+
+#[test]
+fn camel_case_name_is_normalised() {
+    let dir = tempfile::tempdir().unwrap();
+    init_project(dir.path(), &["MyCoolPlugin"]);
+
+    let project = dir.path().join("my-cool-plugin");
+    check_project(&project);
+    let lib = fs::read_to_string(project.join("my_cool_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains("struct MyCoolPlugin"));
+    let main = fs::read_to_string(project.join("my_cool_plugin/src/main.rs")).unwrap();
+    assert!(main.contains("my_cool_plugin::MyCoolPlugin"));
+}
+
+#[test]
+fn display_name_is_used_for_the_plugin_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["my-cool-plugin"]);
+    let lib = fs::read_to_string(project.join("my_cool_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains(r#"const NAME: &'static str = "My Cool Plugin";"#));
+    let bundler: toml::Table = fs::read_to_string(project.join("bundler.toml"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        bundler["my-cool-plugin"]["name"].as_str(),
+        Some("My Cool Plugin")
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(
+        dir.path(),
+        &["my-cool-plugin", "--display-name", "MCP \"Deluxe\""],
+    );
+    check_project(&project);
+    let lib = fs::read_to_string(project.join("my_cool_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains(r#"const NAME: &'static str = "MCP \"Deluxe\"";"#));
+    let editor = fs::read_to_string(project.join("my_cool_plugin/src/editor.rs")).unwrap();
+    assert!(editor.contains(r#""MCP \"Deluxe\"""#));
+}
+
+#[test]
+fn keyword_name_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["init", "match"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Try 'match-plugin'"));
+    assert!(!dir.path().join("match").exists());
+}
+
+#[test]
+fn unknown_gui_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["init", "test-plugin", "--gui", "qt"]);
+
+    assert!(!output.status.success());
+    assert!(!dir.path().join("test-plugin").exists());
+}
+
+/// Runs `nih-bootstrap add plugin` inside `workspace`.
+fn add_plugin(dir: &Path, workspace: &Path, args: &[&str]) -> Output {
+    cli(dir, &[&["add", "plugin"], args].concat())
+        .current_dir(workspace)
+        .output()
+        .expect("failed to run nih-bootstrap")
+}
+
+#[test]
+fn add_plugin_extends_the_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["first-plugin"]);
+
+    let output = add_plugin(
+        dir.path(),
+        &project.join("first_plugin"),
+        &["SecondPlugin", "--gui", "none", "--display-name", "Second"],
+    );
+    assert!(
+        output.status.success(),
+        "nih-bootstrap add plugin failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    check_project(&project);
+    assert_eq!(
+        fs::read_to_string(project.join("Cargo.toml")).unwrap(),
+        "[workspace]\nmembers = [\n    \"first_plugin\",\n    \"second_plugin\",\n    \"xtask\",\n]\nresolver = \"3\"\n"
+    );
+
+    let bundler: toml::Table = fs::read_to_string(project.join("bundler.toml"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        bundler["first-plugin"]["name"].as_str(),
+        Some("First Plugin")
+    );
+    assert_eq!(bundler["second-plugin"]["name"].as_str(), Some("Second"));
+
+    let lib = fs::read_to_string(project.join("second_plugin/src/lib.rs")).unwrap();
+    assert!(lib.contains("struct SecondPlugin"));
+    assert!(!project.join("second_plugin/src/editor.rs").exists());
+}
+
+#[test]
+fn add_plugin_recreates_missing_bundler_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(
+        dir.path(),
+        &["first-plugin", "--display-name", "The \"First\" Plugin"],
+    );
+    fs::remove_file(project.join("bundler.toml")).unwrap();
+
+    let output = add_plugin(dir.path(), &project, &["second-plugin"]);
+    assert!(
+        output.status.success(),
+        "nih-bootstrap add plugin failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bundler = fs::read_to_string(project.join("bundler.toml")).unwrap();
+    assert!(bundler.starts_with("# Metadata for `cargo xtask bundle`"));
+    let bundler: toml::Table = bundler.parse().unwrap();
+    assert_eq!(
+        bundler["first-plugin"]["name"].as_str(),
+        Some("The \"First\" Plugin")
+    );
+    assert_eq!(
+        bundler["second-plugin"]["name"].as_str(),
+        Some("Second Plugin")
+    );
+    assert!(!bundler.contains_key("xtask"));
+}
+
+#[test]
+fn add_plugin_refuses_collisions() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = init_project(dir.path(), &["first-plugin", "--formats", "clap"]);
+    let manifest = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+
+    let output = add_plugin(dir.path(), &project, &["first_plugin"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already has a member"));
+
+    // give the existing plugin the CLAP ID the new one would get
+    let output = run_cli(
+        dir.path(),
+        &[
+            "init",
+            "second-plugin",
+            "--show",
+            "second_plugin/src/lib.rs",
+            "--formats",
+            "clap",
+        ],
+    );
+    let clap_id = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("const CLAP_ID"))
+        .unwrap()
+        .to_string();
+    let lib_path = project.join("first_plugin/src/lib.rs");
+    let lib = fs::read_to_string(&lib_path).unwrap();
+    let lib: Vec<_> = lib
+        .lines()
+        .map(|line| {
+            if line.contains("const CLAP_ID") {
+                clap_id.as_str()
+            } else {
+                line
+            }
+        })
+        .collect();
+    fs::write(&lib_path, lib.join("\n")).unwrap();
+
+    let output = add_plugin(
+        dir.path(),
+        &project,
+        &["second-plugin", "--formats", "clap"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("CLAP ID is already used"));
+
+    assert!(!project.join("second_plugin").exists());
+    assert_eq!(
+        fs::read_to_string(project.join("Cargo.toml")).unwrap(),
+        manifest
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn config_edit_passes_editor_arguments() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = |args: &[&str]| {
+        cli(dir.path(), &[&["config"], args].concat())
+            .env_remove("VISUAL")
+            // the config file is appended as `$0`
+            .env("EDITOR", r#"sh -c 'echo "vendor = \"Acme\"" >> "$0"'"#)
+            .output()
+            .unwrap()
+    };
+
+    let output = config(&["edit"]);
+    assert!(
+        output.status.success(),
+        "config edit failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = config(&["get", "vendor"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Acme");
+}
+
+#[cfg(unix)]
+#[test]
+fn config_edit_rejects_misspelled_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_file = dir.path().join("config/nih-bootstrap/config.toml");
+    fs::create_dir_all(config_file.parent().unwrap()).unwrap();
+    fs::write(&config_file, "vendor = \"Acme\"\n").unwrap();
+
+    let output = cli(dir.path(), &["config", "edit"])
+        .env_remove("VISUAL")
+        .env(
+            "EDITOR",
+            r#"sh -c 'echo "vendor_mail = \"me@example.com\"" >> "$0"'"#,
+        )
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown field `vendor_mail`"));
+
+    // the invalid edits don't replace the config
+    assert_eq!(
+        fs::read_to_string(&config_file).unwrap(),
+        "vendor = \"Acme\"\n"
+    );
+}